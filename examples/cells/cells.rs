@@ -0,0 +1,71 @@
+use locket::buffer::{Buffer, Cell, Modifiers};
+use locket::crossterm::event::{KeyCode, KeyEvent};
+use locket::{font::LIME, Command, Message, Model, Program};
+
+/// Draw a frequently updated dashboard into a cell buffer.
+///
+/// Only the cells that change each tick are written to the terminal.
+fn main() {
+    Program::new(DashboardModel::default())
+        .with_cells()
+        .run()
+        .unwrap();
+}
+
+/// Message sent every tick.
+struct TickMessage;
+
+#[derive(Default)]
+struct DashboardModel {
+    // Number of ticks received.
+    ticks: u64,
+}
+
+impl Model for DashboardModel {
+    fn init(&self) -> Option<Command> {
+        Some(locket::stream(|tx| loop {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            if tx.send(Box::new(TickMessage)).is_err() {
+                return;
+            }
+        }))
+    }
+
+    fn update(&mut self, message: &Message) -> Option<Command> {
+        if let Some(event) = message.downcast_ref::<KeyEvent>() {
+            locket::with_exit!(event);
+        }
+        if message.is::<TickMessage>() {
+            self.ticks += 1;
+        }
+
+        None
+    }
+
+    fn view(&self) -> String {
+        format!("Ticks: {}", self.ticks)
+    }
+
+    #[allow(clippy::manual_is_multiple_of)]
+    fn draw(&self, buffer: &mut Buffer) {
+        let title = Cell {
+            modifiers: Modifiers::BOLD,
+            ..Default::default()
+        };
+        buffer.set_string(0, 0, "Dashboard, press ctrl-c to exit.", title);
+
+        // A grid of counters, each changing at a different rate.
+        for row in 0..8u16 {
+            for col in 0..6u16 {
+                let rate = u64::from(row * 6 + col + 1);
+                let value = format!("{:>8}", self.ticks / rate);
+
+                let style = Cell {
+                    foreground: (self.ticks % rate == 0).then_some(LIME),
+                    ..Default::default()
+                };
+                buffer.set_string(col * 10, row + 2, value, style);
+            }
+        }
+    }
+}
@@ -1,33 +1,75 @@
-use locket::{flush, move_down, move_right, move_up};
+use locket::crossterm::event::{KeyCode, KeyEvent};
+use locket::cursor::{Cursor, Shape};
+use locket::{Command, Message, Model};
 
-/// Manipulate the cursor to fix a typo.
+/// Move the terminal cursor around some text with the arrow keys.
 fn main() {
-    println!("Line 1");
-    println!("Line 3"); // <- Typo here!
-    println!("Line 3");
-    std::thread::sleep(std::time::Duration::from_secs(1));
-
-    // Move the cursor around to fix it.
-    for _ in 0..2 {
-        locket::flush!({
-            move_up!();
-        })
-        .unwrap();
-
-        std::thread::sleep(std::time::Duration::from_millis(500));
+    locket::execute(CursorModel::new()).unwrap();
+}
+
+struct CursorModel {
+    // The lines of text that the cursor moves over.
+    lines: Vec<&'static str>,
+    // The position of the cursor.
+    x: u16,
+    y: u16,
+    // The shape of the cursor, changed with `s`.
+    shape: Shape,
+    // Whether the cursor blinks, changed with `b`.
+    blink: bool,
+}
+
+impl CursorModel {
+    pub fn new() -> Self {
+        Self {
+            lines: vec!["Line 1", "Line 3", "Line 3"],
+            x: 0,
+            y: 0,
+            shape: Shape::Block,
+            blink: true,
+        }
     }
+}
 
-    flush!({
-        move_right!(5);
-        print!("2")
-    })
-    .unwrap();
+impl Model for CursorModel {
+    fn update(&mut self, message: &Message) -> Option<Command> {
+        if let Some(event) = message.downcast_ref::<KeyEvent>() {
+            locket::with_exit!(event);
 
-    std::thread::sleep(std::time::Duration::from_secs(2));
+            match event.code {
+                KeyCode::Up => self.y = self.y.saturating_sub(1),
+                KeyCode::Down => self.y = (self.y + 1).min(self.lines.len() as u16 - 1),
+                KeyCode::Left => self.x = self.x.saturating_sub(1),
+                KeyCode::Right => self.x = (self.x + 1).min(5),
+                KeyCode::Char('s') => {
+                    self.shape = match self.shape {
+                        Shape::Block => Shape::Bar,
+                        Shape::Bar => Shape::Underline,
+                        Shape::Underline => Shape::Block,
+                    }
+                }
+                KeyCode::Char('b') => self.blink = !self.blink,
+                _ => {}
+            }
+        };
 
-    flush!({
-        move_down!();
-        print!("\n");
-    })
-    .unwrap()
+        None
+    }
+
+    fn view(&self) -> String {
+        format!(
+            "{}\n\narrows: move, s: shape ({:?}), b: blink ({})",
+            self.lines.join("\n"),
+            self.shape,
+            self.blink
+        )
+    }
+
+    fn cursor(&self) -> Option<Cursor> {
+        Some(
+            Cursor::new(self.x, self.y)
+                .with_shape(self.shape)
+                .with_blink(self.blink),
+        )
+    }
 }
@@ -6,16 +6,11 @@ fn main() {
     locket::execute(KeypressModel::default()).unwrap();
 }
 
+#[derive(Default)]
 struct KeypressModel {
     last_key: Option<char>,
 }
 
-impl Default for KeypressModel {
-    fn default() -> Self {
-        Self { last_key: None }
-    }
-}
-
 impl Model for KeypressModel {
     fn update(&mut self, message: &Message) -> Option<Command> {
         if let Some(event) = message.downcast_ref::<KeyEvent>() {
@@ -1,7 +1,9 @@
 use crossterm::event::{KeyCode, KeyEvent};
-use locket::{components::List, font::MAROON, Command, Message, Model};
+use locket::event::ResizeEvent;
+use locket::layout::{Constraint, Layout};
+use locket::{components::List, Command, Message, Model};
 
-/// Display a simple paginated list of items.
+/// Display a simple paginated list of items, sized to fit the terminal.
 fn main() {
     locket::execute(ListModel::default()).unwrap();
 }
@@ -19,7 +21,7 @@ impl Default for ListModel {
         assert_eq!(items.len(), 15);
 
         Self {
-            list: List::new(items.into_iter(), 5, MAROON),
+            list: List::new(items.into_iter(), 5, List::HIGHLIGHT),
         }
     }
 }
@@ -30,12 +32,17 @@ impl Model for ListModel {
             locket::with_exit!(event);
         };
 
-        let command = self.list.update(message);
+        // Below the title, the list takes up to 8 lines of the terminal.
+        if let Some(event) = message.downcast_ref::<ResizeEvent>() {
+            let areas =
+                Layout::vertical([Constraint::Length(1), Constraint::Max(8)]).split(event.area());
+            self.list.set_area(areas[1]);
+        }
 
-        command
+        self.list.update(message)
     }
 
     fn view(&self) -> String {
-        self.list.view()
+        format!("Numbers:\n{}", self.list.view())
     }
 }
@@ -0,0 +1,60 @@
+use locket::crossterm::event::{KeyCode, KeyEvent};
+use locket::event::{StdinClosedEvent, StdinEvent};
+use locket::{components::List, Command, Message, Model, Program, Target};
+
+/// Pick one of the lines piped to the program, and print it to stdout.
+///
+/// Try `ls | cargo run --example picker`.
+fn main() {
+    Program::new(PickerModel::default())
+        .with_stdin()
+        .with_target(Target::Tty)
+        .run()
+        .unwrap();
+}
+
+#[derive(Default)]
+struct PickerModel {
+    // Lines read from stdin.
+    lines: Vec<String>,
+    // Nested `List` component, created once stdin is closed.
+    list: Option<List>,
+    // The line picked by the user.
+    picked: Option<String>,
+}
+
+impl Model for PickerModel {
+    fn update(&mut self, message: &Message) -> Option<Command> {
+        if let Some(event) = message.downcast_ref::<KeyEvent>() {
+            locket::with_exit!(event);
+
+            if let (KeyCode::Enter, Some(list)) = (event.code, &self.list) {
+                self.picked = self.lines.get(list.get_overall_position()).cloned();
+                return Some(Box::new(locket::exit));
+            }
+        }
+        if let Some(event) = message.downcast_ref::<StdinEvent>() {
+            self.lines.push(event.0.clone());
+        }
+        if message.is::<StdinClosedEvent>() && !self.lines.is_empty() {
+            self.list = Some(List::new(
+                self.lines.clone().into_iter(),
+                5,
+                List::HIGHLIGHT,
+            ));
+        }
+
+        self.list.as_mut().and_then(|list| list.update(message))
+    }
+
+    fn view(&self) -> String {
+        match &self.list {
+            Some(list) => list.view(),
+            None => format!("Reading... {} lines", self.lines.len()),
+        }
+    }
+
+    fn result(&self) -> Option<String> {
+        self.picked.clone()
+    }
+}
@@ -0,0 +1,68 @@
+use locket::crossterm::event::{KeyCode, KeyEvent};
+use locket::{batch, Command, Message, Model, OnExit, Program};
+
+/// Install a list of packages, committing each to the scrollback when finished.
+fn main() {
+    // Only the installed packages are left behind on exit.
+    Program::new(InstallModel::new(&["foo", "bar", "baz", "qux"]))
+        .on_exit(OnExit::Clear)
+        .run()
+        .unwrap();
+}
+
+/// Message sent when a package has been installed.
+struct InstalledMessage(String);
+
+struct InstallModel {
+    // Packages that have not been installed yet.
+    pending: Vec<String>,
+}
+
+impl InstallModel {
+    pub fn new(packages: &[&str]) -> Self {
+        Self {
+            pending: packages.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    // Return a command that pretends to install the next package.
+    fn install(&self) -> Option<Command> {
+        let package = self.pending.first()?.clone();
+
+        Some(Box::new(move || {
+            std::thread::sleep(std::time::Duration::from_millis(800));
+            Some(Box::new(InstalledMessage(package)))
+        }))
+    }
+}
+
+impl Model for InstallModel {
+    fn init(&self) -> Option<Command> {
+        self.install()
+    }
+
+    fn update(&mut self, message: &Message) -> Option<Command> {
+        if let Some(event) = message.downcast_ref::<KeyEvent>() {
+            locket::with_exit!(event);
+        }
+        if let Some(InstalledMessage(package)) = message.downcast_ref() {
+            self.pending.retain(|p| p != package);
+
+            let print = locket::println(format!("✓ installed {package}"));
+
+            return match self.install() {
+                Some(next) => Some(batch(vec![print, next])),
+                None => Some(print),
+            };
+        }
+
+        None
+    }
+
+    fn view(&self) -> String {
+        match self.pending.first() {
+            Some(package) => format!("Installing {package}..."),
+            None => "Done! Press ctrl-c to exit.".to_string(),
+        }
+    }
+}
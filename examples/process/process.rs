@@ -0,0 +1,83 @@
+use locket::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use locket::process::{Handle, OutputMessage, StatusMessage};
+use locket::{Command, Message, Model};
+
+/// Tail the output of a child process, and display its exit status.
+fn main() {
+    let mut command = std::process::Command::new("sh");
+    command.args([
+        "-c",
+        "for i in 1 2 3 4 5 6 7 8; do echo \"line $i\"; sleep 0.5; done; exit 1",
+    ]);
+
+    locket::execute(ProcessModel::new(command)).unwrap();
+}
+
+struct ProcessModel {
+    // Command to start the process, taken by `init`.
+    command: std::cell::RefCell<Option<Command>>,
+    // Handle used to kill the process.
+    handle: Handle,
+    // The last few lines of output.
+    lines: Vec<String>,
+    // Exit status, once the process has finished.
+    status: Option<String>,
+}
+
+impl ProcessModel {
+    pub fn new(command: std::process::Command) -> Self {
+        let (cmd, handle) = locket::process::spawn(command);
+
+        Self {
+            command: std::cell::RefCell::new(Some(cmd)),
+            handle,
+            lines: Vec::new(),
+            status: None,
+        }
+    }
+}
+
+impl Model for ProcessModel {
+    fn init(&self) -> Option<Command> {
+        self.command.borrow_mut().take()
+    }
+
+    fn update(&mut self, message: &Message) -> Option<Command> {
+        if let Some(event) = message.downcast_ref::<KeyEvent>() {
+            match event.code {
+                // Kill the process, but keep displaying until it has exited.
+                KeyCode::Char('k') => self.handle.kill().unwrap(),
+                KeyCode::Char('c') if event.modifiers == KeyModifiers::CONTROL => {
+                    return Some(Box::new(locket::exit))
+                }
+                _ => {}
+            }
+        }
+        if let Some(output) = message.downcast_ref::<OutputMessage>() {
+            self.lines.push(output.line.clone());
+            if self.lines.len() > 5 {
+                self.lines.remove(0);
+            }
+        }
+        if let Some(status) = message.downcast_ref::<StatusMessage>() {
+            self.status = Some(match &status.status {
+                Ok(status) => status.to_string(),
+                Err(error) => error.to_string(),
+            });
+        }
+
+        None
+    }
+
+    fn view(&self) -> String {
+        format!(
+            "{}\n\n{}",
+            self.lines.join("\n"),
+            self.status
+                .as_ref()
+                .map_or("Running, press 'k' to kill.".to_string(), |s| format!(
+                    "Finished: {s}, press ctrl-c to exit."
+                ))
+        )
+    }
+}
@@ -6,22 +6,13 @@ fn main() {
     locket::execute(ResizeModel::default()).unwrap();
 }
 
+#[derive(Default)]
 struct ResizeModel {
     terminal_x: u16,
     terminal_y: u16,
     moved: bool,
 }
 
-impl Default for ResizeModel {
-    fn default() -> Self {
-        Self {
-            terminal_x: 0,
-            terminal_y: 0,
-            moved: false,
-        }
-    }
-}
-
 impl Model for ResizeModel {
     fn update(&mut self, message: &Message) -> Option<Command> {
         if let Some(event) = message.downcast_ref::<KeyEvent>() {
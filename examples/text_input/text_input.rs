@@ -33,18 +33,14 @@ impl Model for InputModel {
         if let Some(event) = message.downcast_ref::<KeyEvent>() {
             locket::with_exit!(event);
 
-            match event.code {
-                // Display the greeting.
-                KeyCode::Enter => {
-                    if !self.input.buffer().is_empty() {
-                        self.name = Some(self.input.buffer().to_string());
-                        self.input.clear();
-                    }
-
-                    return None;
+            // Display the greeting.
+            if let KeyCode::Enter = event.code {
+                if !self.input.buffer().is_empty() {
+                    self.name = Some(self.input.buffer().to_string());
+                    self.input.clear();
                 }
 
-                _ => {}
+                return None;
             }
         };
 
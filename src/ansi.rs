@@ -0,0 +1,627 @@
+//! Measure and change text that contains escape sequences.
+//!
+//! Text drawn with `font` contains escape sequences, which take up bytes but
+//! no columns, so `str::len` cannot be used to lay it out.
+
+/// Ranges of characters that occupy no columns.
+///
+/// These are mostly combining marks, which are drawn over the previous character.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DC),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x0711, 0x0711),
+    (0x0730, 0x074A),
+    (0x0900, 0x0902),
+    (0x093A, 0x093A),
+    (0x093C, 0x093C),
+    (0x0941, 0x0948),
+    (0x094D, 0x094D),
+    (0x0951, 0x0957),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1160, 0x11FF),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x2028, 0x202E),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0000, 0xE007F),
+    (0xE0100, 0xE01EF),
+];
+
+/// Ranges of characters that occupy two columns.
+///
+/// These are the East Asian Wide and Fullwidth characters, and emoji
+/// that are presented as pictures by default.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x16FE0, 0x16FE4),
+    (0x17000, 0x18AFF),
+    (0x1B000, 0x1B2FF),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335),
+    (0x1F337, 0x1F37C),
+    (0x1F37E, 0x1F393),
+    (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3),
+    (0x1F3E0, 0x1F3F0),
+    (0x1F3F4, 0x1F3F4),
+    (0x1F3F8, 0x1F43E),
+    (0x1F440, 0x1F440),
+    (0x1F442, 0x1F4FC),
+    (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E),
+    (0x1F550, 0x1F567),
+    (0x1F57A, 0x1F57A),
+    (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4),
+    (0x1F5FB, 0x1F64F),
+    (0x1F680, 0x1F6C5),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2),
+    (0x1F6D5, 0x1F6D7),
+    (0x1F6DC, 0x1F6DF),
+    (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F7F0, 0x1F7F0),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+/// Return true if the character is within one of the sorted ranges.
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+
+    ranges
+        .binary_search_by(|&(start, end)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Return the number of columns that the character occupies in a terminal.
+///
+/// Control characters occupy no columns.
+pub fn char_width(c: char) -> usize {
+    if c.is_ascii() {
+        return if c.is_ascii_control() { 0 } else { 1 };
+    }
+    if c.is_control() || in_ranges(c, ZERO_WIDTH) {
+        return 0;
+    }
+    if in_ranges(c, WIDE) {
+        return 2;
+    }
+
+    1
+}
+
+/// Return the length in bytes of the escape sequence at the start of the text.
+///
+/// Returns 0 if the text does not begin with an escape sequence. An incomplete
+/// sequence extends to the end of the text.
+pub fn escape_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&0x1B) {
+        return 0;
+    }
+
+    match bytes.get(1) {
+        // Control Sequence Introducer, terminated by a byte in `@` to `~`.
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7E).contains(b))
+            .map_or(bytes.len(), |i| i + 3),
+        // Operating System Command and other strings, terminated by `BEL` or `ESC \`.
+        Some(b']' | b'P' | b'_' | b'^') => {
+            let mut i = 2;
+            while i < bytes.len() {
+                match bytes[i] {
+                    0x07 => return i + 1,
+                    0x1B if bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+                    _ => i += 1,
+                }
+            }
+
+            bytes.len()
+        }
+        // Two byte sequences, the second byte may not be ASCII.
+        Some(_) => 1 + text[1..].chars().next().map_or(0, char::len_utf8),
+        None => 1,
+    }
+}
+
+/// Return the text without any escape sequences.
+///
+/// # Examples
+///
+/// ```
+/// use locket::ansi::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1B[1mbold\x1B[0m text"), "bold text");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    tokens(text)
+        .filter_map(|token| match token {
+            Token::Text(grapheme) => Some(grapheme),
+            Token::Escape(_) => None,
+        })
+        .collect()
+}
+
+/// Return the number of columns occupied by the widest line of text,
+/// ignoring any escape sequences.
+///
+/// Each grapheme, such as a letter with combining accents or an emoji
+/// sequence, is measured as a whole. Tabs advance to the next multiple
+/// of 8 columns.
+///
+/// # Examples
+///
+/// ```
+/// use locket::ansi::width;
+///
+/// assert_eq!(width("\x1B[31m日本\x1B[0m"), 4);
+/// assert_eq!(width("e\u{301}\nab"), 2);
+/// ```
+pub fn width(text: &str) -> usize {
+    text.split('\n').map(line_width).max().unwrap_or(0)
+}
+
+/// Return the text cut short to fit in `width` columns, ending with the
+/// ellipsis if anything was removed.
+///
+/// Escape sequences are kept, so styles that end after the cut are still
+/// reset. Each line is truncated separately.
+///
+/// # Examples
+///
+/// ```
+/// use locket::ansi::truncate;
+///
+/// assert_eq!(truncate("Hello, World!", 8, "…"), "Hello, …");
+/// assert_eq!(truncate("\x1B[1mHello\x1B[0m", 4, "."), "\x1B[1mHel.\x1B[0m");
+/// assert_eq!(truncate("Hello", 5, "…"), "Hello");
+/// ```
+pub fn truncate(text: &str, width: usize, ellipsis: &str) -> String {
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| truncate_line(line, width, ellipsis))
+        .collect();
+
+    lines.join("\n")
+}
+
+/// Return a single line of text cut short to fit in `width` columns.
+fn truncate_line(line: &str, width: usize, ellipsis: &str) -> String {
+    if line_width(line) <= width {
+        return line.to_string();
+    }
+
+    // An ellipsis that does not fit is itself cut short.
+    let ellipsis = if line_width(ellipsis) > width {
+        truncate_line(ellipsis, width, "")
+    } else {
+        ellipsis.to_string()
+    };
+
+    let available = width - line_width(&ellipsis);
+    let mut truncated = String::with_capacity(line.len());
+    let mut used = 0;
+    let mut cut = false;
+
+    for token in tokens(line) {
+        match token {
+            Token::Escape(sequence) => truncated.push_str(sequence),
+            Token::Text(_) if cut => {}
+            Token::Text(grapheme) => {
+                let width = advance(used, grapheme);
+                if used + width > available {
+                    truncated.push_str(&ellipsis);
+                    cut = true;
+                    continue;
+                }
+
+                truncated.push_str(grapheme);
+                used += width;
+            }
+        }
+    }
+
+    truncated
+}
+
+/// Return the text with lines broken between words, so that each line fits
+/// in `width` columns.
+///
+/// Words that are wider than a line are broken where they reach its end.
+/// Tabs are replaced by the spaces up to the next multiple of 8 columns.
+/// Styles that are applied where a line is broken end with that line, and
+/// are applied again at the start of the next.
+///
+/// # Examples
+///
+/// ```
+/// use locket::ansi::wrap;
+///
+/// assert_eq!(wrap("The quick brown fox", 10), "The quick\nbrown fox");
+/// assert_eq!(
+///     wrap("\x1B[1mbold words\x1B[0m", 5),
+///     "\x1B[1mbold\x1B[0m\n\x1B[1mwords\x1B[0m"
+/// );
+/// ```
+pub fn wrap(text: &str, width: usize) -> String {
+    let width = width.max(1);
+    let mut wrapper = Wrapper::default();
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            wrapper.flush_word(width);
+            wrapper.break_line();
+        }
+
+        for token in tokens(line) {
+            match token {
+                Token::Text(" ") => {
+                    wrapper.flush_word(width);
+                    wrapper.space += 1;
+                }
+                Token::Text("\t") => {
+                    wrapper.flush_word(width);
+                    wrapper.space += advance(wrapper.line_width + wrapper.space, "\t");
+                }
+                Token::Text(grapheme) => {
+                    let grapheme_width = grapheme_width(grapheme);
+                    if wrapper.word_width > 0 && wrapper.word_width + grapheme_width > width {
+                        wrapper.flush_word(width);
+                    }
+
+                    wrapper.word.push(token);
+                    wrapper.word_width += grapheme_width;
+                }
+                Token::Escape(_) => wrapper.word.push(token),
+            }
+        }
+    }
+    wrapper.flush_word(width);
+
+    wrapper.wrapped
+}
+
+/// State of `wrap` while text is being wrapped.
+#[derive(Default)]
+struct Wrapper<'a> {
+    /// The text wrapped so far.
+    wrapped: String,
+    /// The width of the last line of `wrapped`.
+    line_width: usize,
+    /// The number of spaces before `word`.
+    space: usize,
+    /// The word that is not yet placed.
+    word: Vec<Token<'a>>,
+    /// The width of `word`.
+    word_width: usize,
+    /// Select Graphic Rendition sequences placed since the last reset.
+    active: Vec<&'a str>,
+}
+
+impl<'a> Wrapper<'a> {
+    /// Place the word on the current line, or the next if it does not fit.
+    fn flush_word(&mut self, width: usize) {
+        if self.word_width > 0 {
+            if self.line_width + self.space + self.word_width > width && self.line_width > 0 {
+                self.break_line();
+            } else {
+                self.wrapped.extend(std::iter::repeat_n(' ', self.space));
+                self.line_width += self.space;
+            }
+            self.space = 0;
+        }
+
+        for token in std::mem::take(&mut self.word) {
+            match token {
+                Token::Text(grapheme) => self.wrapped.push_str(grapheme),
+                Token::Escape(sequence) => {
+                    self.wrapped.push_str(sequence);
+                    if is_reset(sequence) {
+                        self.active.clear();
+                    } else if sequence.starts_with("\x1B[") && sequence.ends_with('m') {
+                        self.active.push(sequence);
+                    }
+                }
+            }
+        }
+        self.line_width += self.word_width;
+        self.word_width = 0;
+    }
+
+    /// Start a new line, ending the styles on the current line and applying
+    /// them again on the next.
+    fn break_line(&mut self) {
+        if !self.active.is_empty() {
+            self.wrapped.push_str("\x1B[0m");
+        }
+        self.wrapped.push('\n');
+        for sequence in &self.active {
+            self.wrapped.push_str(sequence);
+        }
+
+        self.line_width = 0;
+        self.space = 0;
+    }
+}
+
+/// A part of text, either an escape sequence or a grapheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Escape(&'a str),
+    Text(&'a str),
+}
+
+/// Return the escape sequences and graphemes of the text, in order.
+pub(crate) fn tokens(text: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        if i >= text.len() {
+            return None;
+        }
+
+        let len = escape_len(&text[i..]);
+        let token = if len > 0 {
+            Token::Escape(&text[i..i + len])
+        } else {
+            Token::Text(&text[i..i + grapheme_len(&text[i..])])
+        };
+
+        let part = match token {
+            Token::Escape(part) | Token::Text(part) => part,
+        };
+        i += part.len();
+        Some(token)
+    })
+}
+
+/// Zero width joiner, which joins emoji into a single picture.
+const ZWJ: char = '\u{200D}';
+
+/// Variation selector that presents the previous character as an emoji.
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+/// Return true if the character is a regional indicator, two of which
+/// make a flag.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Return the length in bytes of the grapheme at the start of the text,
+/// which must not be empty.
+///
+/// A grapheme is a character followed by any characters that are drawn
+/// with it, such as combining marks, or the rest of an emoji sequence.
+pub(crate) fn grapheme_len(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    let Some((_, first)) = chars.next() else {
+        return 0;
+    };
+    if first == '\r' && text[1..].starts_with('\n') {
+        return 2;
+    }
+    if first.is_ascii_control() {
+        return 1;
+    }
+
+    let mut previous = first;
+    let mut regional = is_regional_indicator(first);
+    while let Some(&(i, c)) = chars.peek() {
+        let joined = if previous == ZWJ {
+            !c.is_ascii_control()
+        } else if regional && is_regional_indicator(c) {
+            regional = false;
+            true
+        } else {
+            c == ZWJ || (!c.is_ascii() && !c.is_control() && in_ranges(c, ZERO_WIDTH))
+        };
+        if !joined {
+            return i;
+        }
+
+        previous = c;
+        chars.next();
+    }
+
+    text.len()
+}
+
+/// Return the number of columns that the grapheme occupies in a terminal.
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    let mut chars = grapheme.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+
+    // Flags, and characters presented as emoji, are as wide as emoji.
+    if is_regional_indicator(first) || grapheme.contains(EMOJI_PRESENTATION) {
+        return char_width(first).max(2);
+    }
+
+    char_width(first)
+}
+
+/// Return the number of columns occupied by a single line of text.
+fn line_width(line: &str) -> usize {
+    let mut width = 0;
+
+    for token in tokens(line) {
+        if let Token::Text(grapheme) = token {
+            width += advance(width, grapheme);
+        }
+    }
+
+    width
+}
+
+/// Return the number of columns occupied by a grapheme that starts at
+/// `column`, where a tab advances to the next multiple of 8 columns.
+pub(crate) fn advance(column: usize, grapheme: &str) -> usize {
+    match grapheme {
+        "\t" => 8 - column % 8,
+        _ => grapheme_width(grapheme),
+    }
+}
+
+/// Return true if the escape sequence resets all styles.
+pub(crate) fn is_reset(sequence: &str) -> bool {
+    let Some(params) = sequence
+        .strip_prefix("\x1B[")
+        .and_then(|s| s.strip_suffix('m'))
+    else {
+        return false;
+    };
+
+    let mut params = params.split(';');
+    while let Some(param) = params.next() {
+        match param {
+            "" | "0" => return true,
+            // Skip the arguments of extended colors, which may contain a 0.
+            "38" | "48" | "58" => match params.next() {
+                Some("5") => {
+                    params.next();
+                }
+                Some("2") => {
+                    params.nth(2);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ansi::{truncate, width, wrap};
+
+    #[test]
+    fn test_width() {
+        // Combining marks, emoji sequences and flags are measured whole.
+        assert_eq!(width("cafe\u{301}"), 4);
+        assert_eq!(width("👩\u{200D}👩\u{200D}👦"), 2);
+        assert_eq!(width("🇯🇵"), 2);
+        assert_eq!(width("❤\u{FE0F}"), 2);
+        assert_eq!(width("a\tb"), 9);
+    }
+
+    #[test]
+    fn test_truncate() {
+        // Wide characters are not split.
+        assert_eq!(truncate("日本語", 5, "…"), "日本…");
+        assert_eq!(truncate("日本語", 4, ""), "日本");
+        assert_eq!(
+            truncate("\x1B[1mab\x1B[0mcd\nxy", 3, "…"),
+            "\x1B[1mab\x1B[0m…\nxy"
+        );
+
+        // An ellipsis wider than the width is cut short too.
+        assert_eq!(truncate("abcdef", 2, "..."), "..");
+        assert_eq!(truncate("abcdef", 0, "…"), "");
+
+        // Tabs are measured as they are by `width`.
+        assert_eq!(truncate("a\tbc", 9, "…"), "a\t…");
+        assert_eq!(width(&truncate("ab\tcd", 8, "")), 8);
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("a bb ccc", 4), "a bb\nccc");
+        assert_eq!(wrap("abcdefgh ij", 3), "abc\ndef\ngh\nij");
+        assert_eq!(wrap("one\ntwo three", 5), "one\ntwo\nthree");
+        assert_eq!(wrap("日本語", 4), "日本\n語");
+
+        // Tabs are measured as they are by `width`, so every line fits.
+        assert_eq!(wrap("a\tb c", 10), "a       b\nc");
+        assert!(wrap("ab\tcd\tef", 9).lines().all(|line| width(line) <= 9));
+    }
+}
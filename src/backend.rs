@@ -0,0 +1,183 @@
+//! Terminals that a `Program` can run on.
+//!
+//! A `Backend` is where the interface is written, and where events are
+//! read from. `CrosstermBackend` is used unless another backend is given
+//! to `Program::with_backend`.
+
+pub use self::crossterm_backend::CrosstermBackend;
+pub use self::libc_backend::LibcBackend;
+pub use self::stream_backend::StreamBackend;
+pub use self::test_backend::TestBackend;
+
+mod crossterm_backend;
+mod input;
+mod libc_backend;
+mod stream_backend;
+mod test_backend;
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+
+use crossterm::event::Event;
+
+use crate::font::{self, Color};
+use crate::foreign;
+
+/// A terminal that a `Program` can run on.
+///
+/// Frames are drawn by writing escape sequences to the backend.
+pub trait Backend: std::io::Write {
+    /// Return the (x, y) size of the terminal.
+    fn size(&self) -> std::io::Result<(u16, u16)>;
+
+    /// Return true if the output is a terminal.
+    ///
+    /// When it is not, the program runs without reading events or writing
+    /// escape sequences, and only the final view and printed text are written.
+    fn is_terminal(&self) -> bool {
+        true
+    }
+
+    /// Return the source of events for the terminal.
+    ///
+    /// Called a single time, before the program starts. Events are read
+    /// on a separate thread.
+    fn events(&mut self) -> std::io::Result<Box<dyn EventSource>>;
+
+    /// Stop the terminal from echoing and buffering input.
+    fn enable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    /// Restore the mode changed by `enable_raw_mode`.
+    fn disable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    /// Show the cursor.
+    fn show_cursor(&mut self) -> std::io::Result<()> {
+        self.write_all(b"\x1B[?25h")?;
+        self.flush()
+    }
+
+    /// Hide the cursor.
+    fn hide_cursor(&mut self) -> std::io::Result<()> {
+        self.write_all(b"\x1B[?25l")?;
+        self.flush()
+    }
+
+    /// Ask the terminal for its background color, waiting at most `timeout`
+    /// for the answer.
+    ///
+    /// Called a single time, after raw mode is enabled and before `events`.
+    /// Returns `None` by default, or if the terminal does not answer. Keys
+    /// typed while waiting for the answer are delivered by `events`.
+    fn query_background(&mut self, timeout: Duration) -> std::io::Result<Option<Color>> {
+        let _ = timeout;
+        Ok(None)
+    }
+}
+
+/// A blocking source of terminal events.
+pub trait EventSource: Send {
+    /// Wait for the next event.
+    ///
+    /// Returns `None` when there will be no more events.
+    fn read(&mut self) -> std::io::Result<Option<Event>>;
+}
+
+/// Ask the terminal for its background color with `OSC 11`, and read the
+/// answer from `tty`.
+///
+/// A Primary Device Attributes query follows, which every terminal answers,
+/// so that terminals that ignore `OSC 11` are not waited on until `timeout`.
+///
+/// Returns the color, and the bytes read that are not part of an answer,
+/// such as keys typed in the meantime.
+fn query_background(
+    mut tty: &File,
+    timeout: Duration,
+) -> std::io::Result<(Option<Color>, Vec<u8>)> {
+    tty.write_all(b"\x1B]11;?\x07\x1B[c")?;
+    tty.flush()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut answer = Vec::new();
+    let mut buffer = [0; 256];
+
+    while !has_device_attributes(&answer) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let ready = unsafe { foreign::poll_read(&[tty.as_raw_fd()], remaining.as_millis() as _) }?;
+        if !ready[0] {
+            break;
+        }
+
+        match tty.read(&mut buffer)? {
+            0 => break,
+            len => answer.extend_from_slice(&buffer[..len]),
+        }
+    }
+
+    Ok((font::parse_background(&answer), strip_answers(&answer)))
+}
+
+/// Return the bytes without the answers to the queries written by
+/// `query_background`.
+fn strip_answers(bytes: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let len = if rest.starts_with(b"\x1B]11;") {
+            // Terminated by `BEL` or `ST`.
+            rest.iter().enumerate().find_map(|(j, b)| match b {
+                b'\x07' => Some(j + 1),
+                b'\\' if rest[j - 1] == 0x1B => Some(j + 1),
+                _ => None,
+            })
+        } else if rest.starts_with(b"\x1B[?") {
+            rest[3..]
+                .iter()
+                .position(|b| !b.is_ascii_digit() && *b != b';')
+                .filter(|j| rest[3 + j] == b'c')
+                .map(|j| j + 4)
+        } else {
+            None
+        };
+
+        match len {
+            Some(len) => i += len,
+            None => {
+                stripped.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    stripped
+}
+
+/// Return true if the bytes contain an answer to a Primary Device Attributes
+/// query, `ESC [ ? <params> c`.
+fn has_device_attributes(bytes: &[u8]) -> bool {
+    bytes.windows(3).enumerate().any(|(i, window)| {
+        window == b"\x1B[?"
+            && bytes[i + 3..]
+                .iter()
+                .find(|b| !b.is_ascii_digit() && **b != b';')
+                .is_some_and(|b| *b == b'c')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::strip_answers;
+
+    #[test]
+    fn test_strip_answers() {
+        // Keys typed before, between and after the answers are kept.
+        let bytes = b"a\x1B]11;rgb:ffff/ffff/ffff\x1B\\b\x1B[?62;22c\x1B[Ac";
+        assert_eq!(strip_answers(bytes), b"ab\x1B[Ac");
+
+        assert_eq!(strip_answers(b"\x1B]11;rgb:0/0/0\x07q"), b"q");
+    }
+}
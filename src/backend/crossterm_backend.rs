@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use crossterm::event::Event;
+
+use crate::backend::input::Parser;
+use crate::backend::{Backend, EventSource};
+use crate::font::Color;
+use crate::Target;
+
+/// Backend that uses `crossterm` to configure the terminal and read events.
+///
+/// This is the default backend.
+pub struct CrosstermBackend {
+    /// Where the interface is drawn.
+    out: Box<dyn Write>,
+    /// True if `out` is a terminal.
+    terminal: bool,
+    /// Keys typed while the background was queried, which are read before
+    /// any other event.
+    typed: Vec<u8>,
+}
+
+impl CrosstermBackend {
+    /// Return a new instance of `CrosstermBackend` drawing to the target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target is `Target::Tty` and there is no
+    /// controlling terminal.
+    pub fn new(target: Target) -> std::io::Result<Self> {
+        let (out, terminal): (Box<dyn Write>, bool) = match target {
+            Target::Stdout => (Box::new(std::io::stdout()), std::io::stdout().is_terminal()),
+            Target::Stderr => (Box::new(std::io::stderr()), std::io::stderr().is_terminal()),
+            Target::Tty => (
+                Box::new(std::fs::OpenOptions::new().write(true).open("/dev/tty")?),
+                true,
+            ),
+        };
+
+        Ok(Self {
+            out,
+            terminal,
+            typed: Vec::new(),
+        })
+    }
+}
+
+impl Write for CrosstermBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.out.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn size(&self) -> std::io::Result<(u16, u16)> {
+        crossterm::terminal::size()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    fn events(&mut self) -> std::io::Result<Box<dyn EventSource>> {
+        // Keys are read from `/dev/tty` when standard input is not a terminal,
+        // so make sure that it exists before taking over.
+        if !std::io::stdin().is_terminal() {
+            std::fs::File::open("/dev/tty")?;
+        }
+
+        // The keys are complete, so nothing is left waiting in the parser.
+        let mut parser = Parser::default();
+        let mut pending: VecDeque<Event> = parser.feed(&std::mem::take(&mut self.typed)).into();
+        pending.extend(parser.flush());
+
+        Ok(Box::new(CrosstermEvents(pending)))
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        crossterm::terminal::enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        crossterm::terminal::disable_raw_mode()
+    }
+
+    fn query_background(&mut self, timeout: Duration) -> std::io::Result<Option<Color>> {
+        // The answer is read before `crossterm` starts reading events, which
+        // would discard it.
+        let tty = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")?;
+
+        let (color, typed) = super::query_background(&tty, timeout)?;
+        self.typed.extend(typed);
+
+        Ok(color)
+    }
+}
+
+/// Reads events with `crossterm::event::read`, after the events that were
+/// read before it started.
+struct CrosstermEvents(VecDeque<Event>);
+
+impl EventSource for CrosstermEvents {
+    fn read(&mut self) -> std::io::Result<Option<Event>> {
+        match self.0.pop_front() {
+            Some(event) => Ok(Some(event)),
+            None => crossterm::event::read().map(Some),
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::backend::EventSource;
+
+/// How long to wait for the rest of an escape sequence, before deciding
+/// that the escape key was pressed on its own.
+const ESCAPE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Input read from a terminal, before it is parsed.
+pub enum Input {
+    /// Bytes read from the terminal.
+    Bytes(Vec<u8>),
+    /// The new (x, y) size of the terminal.
+    Resize(u16, u16),
+}
+
+/// Event source that parses the input received on a channel.
+///
+/// There are no more events once all senders have been dropped.
+pub struct InputEvents {
+    /// Receives input from the threads reading the terminal.
+    rx: Receiver<Input>,
+    /// Parser holding any incomplete escape sequence.
+    parser: Parser,
+    /// Events parsed but not yet read.
+    pending: VecDeque<Event>,
+}
+
+impl InputEvents {
+    /// Return a new instance of `InputEvents`.
+    pub fn new(rx: Receiver<Input>) -> Self {
+        Self {
+            rx,
+            parser: Parser::default(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl EventSource for InputEvents {
+    fn read(&mut self) -> std::io::Result<Option<Event>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let input = if self.parser.is_pending() {
+                match self.rx.recv_timeout(ESCAPE_TIMEOUT) {
+                    Ok(input) => input,
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.pending.extend(self.parser.flush());
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        self.pending.extend(self.parser.flush());
+                        return Ok(self.pending.pop_front());
+                    }
+                }
+            } else {
+                match self.rx.recv() {
+                    Ok(input) => input,
+                    Err(_) => return Ok(None),
+                }
+            };
+
+            match input {
+                Input::Bytes(bytes) => self.pending.extend(self.parser.feed(&bytes)),
+                Input::Resize(x, y) => self.pending.push_back(Event::Resize(x, y)),
+            }
+        }
+    }
+}
+
+/// Result of parsing the start of a buffer.
+enum Parsed {
+    /// An event, and the number of bytes it was parsed from.
+    Event(Event, usize),
+    /// A number of bytes that do not describe a supported event.
+    Skip(usize),
+    /// The buffer ends within a sequence.
+    Incomplete,
+}
+
+/// Parses terminal input into events.
+///
+/// Input may be split at any point, so incomplete sequences are kept
+/// until more input arrives.
+#[derive(Default)]
+pub struct Parser {
+    /// Bytes of an incomplete sequence.
+    buffer: Vec<u8>,
+}
+
+impl Parser {
+    /// Parse the bytes, returning each complete event.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Event> {
+        self.buffer.extend_from_slice(bytes);
+        self.parse(false)
+    }
+
+    /// Return true if an incomplete sequence is waiting for more input.
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Parse any incomplete sequence as if no more input will arrive.
+    ///
+    /// A lone escape byte becomes the escape key.
+    pub fn flush(&mut self) -> Vec<Event> {
+        self.parse(true)
+    }
+
+    fn parse(&mut self, complete: bool) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut offset = 0;
+
+        while offset < self.buffer.len() {
+            match parse(&self.buffer[offset..], complete) {
+                Parsed::Event(event, len) => {
+                    events.push(event);
+                    offset += len;
+                }
+                Parsed::Skip(len) => offset += len,
+                Parsed::Incomplete => break,
+            }
+        }
+
+        self.buffer.drain(..offset);
+        events
+    }
+}
+
+/// Return a key press event.
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+/// Parse the event at the start of the buffer.
+///
+/// When `complete` is true, no more input will arrive to finish a sequence.
+fn parse(buffer: &[u8], complete: bool) -> Parsed {
+    match buffer[0] {
+        0x1B => parse_escape(buffer, complete),
+        b'\r' => Parsed::Event(key(KeyCode::Enter, KeyModifiers::NONE), 1),
+        b'\t' => Parsed::Event(key(KeyCode::Tab, KeyModifiers::NONE), 1),
+        0x7F => Parsed::Event(key(KeyCode::Backspace, KeyModifiers::NONE), 1),
+        0x00 => Parsed::Event(key(KeyCode::Char(' '), KeyModifiers::CONTROL), 1),
+        b @ 0x01..=0x1A => Parsed::Event(
+            key(
+                KeyCode::Char((b - 0x01 + b'a') as char),
+                KeyModifiers::CONTROL,
+            ),
+            1,
+        ),
+        b @ 0x1C..=0x1F => Parsed::Event(
+            key(
+                KeyCode::Char((b - 0x1C + b'4') as char),
+                KeyModifiers::CONTROL,
+            ),
+            1,
+        ),
+        _ => parse_char(buffer, complete),
+    }
+}
+
+/// Parse a UTF-8 encoded character.
+fn parse_char(buffer: &[u8], complete: bool) -> Parsed {
+    let len = match buffer[0] {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return Parsed::Skip(1),
+    };
+
+    if buffer.len() < len {
+        return if complete {
+            Parsed::Skip(buffer.len())
+        } else {
+            Parsed::Incomplete
+        };
+    }
+
+    match std::str::from_utf8(&buffer[..len]) {
+        Ok(s) => {
+            let c = s.chars().next().unwrap();
+            let modifiers = if c.is_uppercase() {
+                KeyModifiers::SHIFT
+            } else {
+                KeyModifiers::NONE
+            };
+
+            Parsed::Event(key(KeyCode::Char(c), modifiers), len)
+        }
+        Err(_) => Parsed::Skip(1),
+    }
+}
+
+/// Parse a sequence beginning with an escape byte.
+fn parse_escape(buffer: &[u8], complete: bool) -> Parsed {
+    let esc = || Parsed::Event(key(KeyCode::Esc, KeyModifiers::NONE), 1);
+
+    match buffer.get(1) {
+        None if complete => esc(),
+        None => Parsed::Incomplete,
+        Some(0x1B) => esc(),
+        Some(b'[') => match buffer[2..].iter().position(|b| (0x40..=0x7E).contains(b)) {
+            Some(end) => {
+                let len = end + 3;
+                match parse_csi(&buffer[2..len - 1], buffer[len - 1]) {
+                    Some(event) => Parsed::Event(event, len),
+                    None => Parsed::Skip(len),
+                }
+            }
+            None if complete => Parsed::Skip(buffer.len()),
+            None => Parsed::Incomplete,
+        },
+        Some(b'O') => match buffer.get(2) {
+            Some(b) => match ss3_code(*b) {
+                Some(code) => Parsed::Event(key(code, KeyModifiers::NONE), 3),
+                None => Parsed::Skip(3),
+            },
+            None if complete => Parsed::Event(
+                key(KeyCode::Char('O'), KeyModifiers::ALT | KeyModifiers::SHIFT),
+                2,
+            ),
+            None => Parsed::Incomplete,
+        },
+        // Operating System Commands, such as the answer to a query of the
+        // background color, end with `BEL` or `ESC \`.
+        Some(b']') => match buffer[2..].iter().position(|b| *b == 0x07 || *b == 0x1B) {
+            Some(end) if buffer[end + 2] == 0x07 => Parsed::Skip(end + 3),
+            Some(end) if buffer.get(end + 3).is_some() => Parsed::Skip(end + 4),
+            _ if complete => Parsed::Skip(buffer.len()),
+            _ => Parsed::Incomplete,
+        },
+        // Escape followed by a key is the key with alt held.
+        Some(_) => match parse(&buffer[1..], complete) {
+            Parsed::Event(Event::Key(mut event), len) => {
+                event.modifiers |= KeyModifiers::ALT;
+                Parsed::Event(Event::Key(event), len + 1)
+            }
+            Parsed::Event(_, len) | Parsed::Skip(len) => Parsed::Skip(len + 1),
+            Parsed::Incomplete => Parsed::Incomplete,
+        },
+    }
+}
+
+/// Return the key for the final byte of a `ESC O` sequence.
+fn ss3_code(b: u8) -> Option<KeyCode> {
+    Some(match b {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        _ => return None,
+    })
+}
+
+/// Return the modifiers encoded in a parameter, which is one more than
+/// a bit mask of shift, alt and control.
+fn modifiers(param: u16) -> KeyModifiers {
+    let mask = param.saturating_sub(1);
+
+    let mut modifiers = KeyModifiers::NONE;
+    if mask & 1 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if mask & 2 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if mask & 4 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    modifiers
+}
+
+/// Parse a Control Sequence, given its parameters and final byte.
+fn parse_csi(params: &[u8], end: u8) -> Option<Event> {
+    let params = std::str::from_utf8(params).ok()?;
+
+    if let Some(params) = params.strip_prefix('<') {
+        return parse_sgr_mouse(params, end);
+    }
+
+    let mut numbers = params.split(';').map(|p| p.parse::<u16>().unwrap_or(1));
+    let first = numbers.next().unwrap_or(1);
+    let modifiers = modifiers(numbers.next().unwrap_or(1));
+
+    let code = match end {
+        b'Z' => return Some(key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+        b'I' => return Some(Event::FocusGained),
+        b'O' => return Some(Event::FocusLost),
+        b'~' => match first {
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            n @ 11..=15 => KeyCode::F((n - 10) as u8),
+            n @ 17..=21 => KeyCode::F((n - 11) as u8),
+            n @ 23..=24 => KeyCode::F((n - 12) as u8),
+            _ => return None,
+        },
+        b => ss3_code(b)?,
+    };
+
+    Some(key(code, modifiers))
+}
+
+/// Parse the parameters of an SGR mouse sequence, `ESC [ < b ; x ; y M`.
+fn parse_sgr_mouse(params: &str, end: u8) -> Option<Event> {
+    let mut numbers = params.split(';').map(|p| p.parse::<u16>().ok());
+    let code = numbers.next()??;
+    let column = numbers.next()??.saturating_sub(1);
+    let row = numbers.next()??.saturating_sub(1);
+
+    let button = match code & 0b11 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        _ => MouseButton::Right,
+    };
+
+    let kind = if code & 64 != 0 {
+        match code & 0b11 {
+            0 => MouseEventKind::ScrollUp,
+            1 => MouseEventKind::ScrollDown,
+            2 => MouseEventKind::ScrollLeft,
+            _ => MouseEventKind::ScrollRight,
+        }
+    } else if code & 32 != 0 {
+        if code & 0b11 == 3 {
+            MouseEventKind::Moved
+        } else {
+            MouseEventKind::Drag(button)
+        }
+    } else if end == b'm' {
+        MouseEventKind::Up(button)
+    } else {
+        MouseEventKind::Down(button)
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    if code & 4 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if code & 8 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if code & 16 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    Some(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+
+    use crate::backend::input::Parser;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let mut parser = Parser::default();
+
+        assert_eq!(
+            parser.feed(b"aB\x03\r\x1B[A\x1B[1;5C\x1B[3~\x1BOP\x1Bx"),
+            vec![
+                key(KeyCode::Char('a'), KeyModifiers::NONE),
+                key(KeyCode::Char('B'), KeyModifiers::SHIFT),
+                key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                key(KeyCode::Enter, KeyModifiers::NONE),
+                key(KeyCode::Up, KeyModifiers::NONE),
+                key(KeyCode::Right, KeyModifiers::CONTROL),
+                key(KeyCode::Delete, KeyModifiers::NONE),
+                key(KeyCode::F(1), KeyModifiers::NONE),
+                key(KeyCode::Char('x'), KeyModifiers::ALT),
+            ]
+        );
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn test_parse_split() {
+        let mut parser = Parser::default();
+
+        // Sequences and characters may be split across reads.
+        assert_eq!(parser.feed(b"\x1B["), vec![]);
+        assert_eq!(
+            parser.feed(b"B\xE6\x97"),
+            vec![key(KeyCode::Down, KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            parser.feed(b"\xA5"),
+            vec![key(KeyCode::Char('日'), KeyModifiers::NONE)]
+        );
+
+        // A lone escape is the escape key, once no more input arrives.
+        assert_eq!(parser.feed(b"\x1B"), vec![]);
+        assert!(parser.is_pending());
+        assert_eq!(parser.flush(), vec![key(KeyCode::Esc, KeyModifiers::NONE)]);
+
+        // An answer to a query that arrives late is not taken as keys.
+        assert_eq!(parser.feed(b"\x1B]11;rgb:ffff/"), vec![]);
+        assert_eq!(
+            parser.feed(b"ffff/ffff\x1B\\a"),
+            vec![key(KeyCode::Char('a'), KeyModifiers::NONE)]
+        );
+    }
+
+    #[test]
+    fn test_parse_mouse() {
+        let mut parser = Parser::default();
+
+        assert_eq!(
+            parser.feed(b"\x1B[<0;10;5M\x1B[<65;1;1M"),
+            vec![
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column: 9,
+                    row: 4,
+                    modifiers: KeyModifiers::NONE,
+                }),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::ScrollDown,
+                    column: 0,
+                    row: 0,
+                    modifiers: KeyModifiers::NONE,
+                }),
+            ]
+        );
+    }
+}
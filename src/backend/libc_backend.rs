@@ -0,0 +1,166 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backend::input::{Input, InputEvents};
+use crate::backend::{Backend, EventSource};
+use crate::font::Color;
+use crate::foreign;
+
+/// How often the reader thread checks whether the backend was dropped,
+/// in milliseconds.
+const POLL_TIMEOUT: libc::c_int = 100;
+
+/// Backend that configures the controlling terminal, `/dev/tty`, with libc.
+///
+/// Input is parsed by Locket rather than `crossterm`, and window size
+/// changes are detected by handling `SIGWINCH`.
+pub struct LibcBackend {
+    /// The controlling terminal.
+    tty: File,
+    /// The mode of the terminal before raw mode was enabled.
+    mode: Option<libc::termios>,
+    /// Set when the backend is dropped, to stop the reader thread.
+    closed: Arc<AtomicBool>,
+    /// Registrations of the pipes notified of window size changes, which
+    /// are removed when the backend is dropped.
+    resize: Vec<foreign::ResizeWatch>,
+    /// Keys typed while the background was queried, which are read before
+    /// any other input.
+    typed: Vec<u8>,
+}
+
+impl LibcBackend {
+    /// Return a new instance of `LibcBackend`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no controlling terminal.
+    pub fn new() -> std::io::Result<Self> {
+        let tty = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")?;
+
+        Ok(Self {
+            tty,
+            mode: None,
+            closed: Arc::new(AtomicBool::new(false)),
+            resize: Vec::new(),
+            typed: Vec::new(),
+        })
+    }
+}
+
+impl Write for LibcBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.tty.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.tty.flush()
+    }
+}
+
+impl Backend for LibcBackend {
+    fn size(&self) -> std::io::Result<(u16, u16)> {
+        let (row, col) = unsafe { foreign::get_ui_size(self.tty.as_raw_fd()) }?;
+        Ok((col, row))
+    }
+
+    fn events(&mut self) -> std::io::Result<Box<dyn EventSource>> {
+        let tty = self.tty.try_clone()?;
+        let (resize, watch) = unsafe { foreign::watch_resize() }?;
+        self.resize.push(watch);
+        let closed = self.closed.clone();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        if !self.typed.is_empty() {
+            let _ = tx.send(Input::Bytes(std::mem::take(&mut self.typed)));
+        }
+        std::thread::spawn(move || read_input(tty, resize, closed, tx));
+
+        Ok(Box::new(InputEvents::new(rx)))
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        if self.mode.is_none() {
+            self.mode = Some(unsafe { foreign::enable_raw_mode(self.tty.as_raw_fd()) }?);
+        }
+
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        if let Some(mode) = self.mode.take() {
+            unsafe { foreign::set_mode(self.tty.as_raw_fd(), &mode) }?;
+        }
+
+        Ok(())
+    }
+
+    fn query_background(&mut self, timeout: Duration) -> std::io::Result<Option<Color>> {
+        let (color, typed) = super::query_background(&self.tty, timeout)?;
+        self.typed.extend(typed);
+
+        Ok(color)
+    }
+}
+
+impl Drop for LibcBackend {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        let _ = self.disable_raw_mode();
+    }
+}
+
+/// Send the bytes read from the terminal, and its size each time the window
+/// is resized, until the backend is dropped.
+fn read_input(mut tty: File, resize: OwnedFd, closed: Arc<AtomicBool>, tx: Sender<Input>) {
+    let mut resize = File::from(resize);
+    let fds = [tty.as_raw_fd(), resize.as_raw_fd()];
+    let mut buffer = [0; 1024];
+
+    // Cleared once the pipe has been closed, after which only the terminal is read.
+    let mut watching = true;
+
+    while !closed.load(Ordering::SeqCst) {
+        let fds = if watching { &fds[..] } else { &fds[..1] };
+        let Ok(ready) = (unsafe { foreign::poll_read(fds, POLL_TIMEOUT) }) else {
+            return;
+        };
+
+        if ready.get(1) == Some(&true) {
+            // Several signals may have arrived, only the latest size matters.
+            match resize.read(&mut buffer) {
+                Ok(0) => watching = false,
+                Ok(_) => {
+                    if let Ok((row, col)) = unsafe { foreign::get_ui_size(fds[0]) } {
+                        if tx.send(Input::Resize(col, row)).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => watching = false,
+            }
+        }
+
+        if ready[0] {
+            let len = match tty.read(&mut buffer) {
+                Ok(0) => return,
+                Ok(len) => len,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => return,
+            };
+
+            if tx.send(Input::Bytes(buffer[..len].to_vec())).is_err() {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::{Read, Write};
+use std::sync::mpsc::{Receiver, Sender};
+
+use crate::backend::input::{Input, InputEvents};
+use crate::backend::{Backend, EventSource};
+
+/// Backend that runs over any pair of streams, such as a channel of an SSH
+/// server or one side of a pseudoterminal.
+///
+/// The size of the window is supplied by the caller, and each new (x, y)
+/// size is received on `resize`. Since the streams do not belong to the
+/// process's terminal, many programs can run in one process, each with
+/// its own backend.
+///
+/// Raw mode is not changed, the other side of the streams is expected to
+/// deliver input unbuffered, as an SSH client does when it requests a
+/// pseudoterminal.
+///
+/// # Examples
+///
+/// ```no_run
+/// # struct App;
+/// # impl locket::Model for App {
+/// #     fn update(&mut self, _: &locket::Message) -> Option<locket::Command> { None }
+/// #     fn view(&self) -> String { String::new() }
+/// # }
+/// use locket::{backend::StreamBackend, Program};
+///
+/// let listener = std::net::TcpListener::bind("127.0.0.1:7000").unwrap();
+/// for stream in listener.incoming() {
+///     let stream = stream.unwrap();
+///     let input = stream.try_clone().unwrap();
+///     let (_resize_tx, resize_rx) = std::sync::mpsc::channel();
+///
+///     std::thread::spawn(move || {
+///         let backend = StreamBackend::new(input, stream, (80, 24), resize_rx);
+///         Program::new(App).with_backend(backend).run()
+///     });
+/// }
+/// ```
+pub struct StreamBackend<W>
+where
+    W: Write,
+{
+    /// Where the interface is drawn.
+    output: W,
+    /// The initial (x, y) size of the window.
+    size: (u16, u16),
+    /// Input stream, taken by `events`.
+    input: Option<Box<dyn Read + Send>>,
+    /// Receives each new (x, y) size of the window, taken by `events`.
+    resize: Option<Receiver<(u16, u16)>>,
+}
+
+impl<W> StreamBackend<W>
+where
+    W: Write,
+{
+    /// Return a new instance of `StreamBackend`.
+    ///
+    /// Keys are read from `input`, and the interface is written to `output`.
+    /// The size is the initial (x, y) size of the window.
+    pub fn new<R>(input: R, output: W, size: (u16, u16), resize: Receiver<(u16, u16)>) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        Self {
+            output,
+            size,
+            input: Some(Box::new(input)),
+            resize: Some(resize),
+        }
+    }
+}
+
+impl<W> Write for StreamBackend<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+impl<W> Backend for StreamBackend<W>
+where
+    W: Write,
+{
+    fn size(&self) -> std::io::Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn events(&mut self) -> std::io::Result<Box<dyn EventSource>> {
+        let (Some(input), Some(resize)) = (self.input.take(), self.resize.take()) else {
+            return Err(std::io::Error::other(
+                "events can only be taken from a backend once",
+            ));
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let resize_tx = tx.clone();
+
+        std::thread::spawn(move || read_input(input, tx));
+        std::thread::spawn(move || {
+            for (x, y) in resize {
+                if resize_tx.send(Input::Resize(x, y)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Box::new(InputEvents::new(rx)))
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Send the bytes read from the input until it is closed.
+fn read_input(mut input: Box<dyn Read + Send>, tx: Sender<Input>) {
+    let mut buffer = [0; 1024];
+
+    loop {
+        let len = match input.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(len) => len,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+
+        if tx.send(Input::Bytes(buffer[..len].to_vec())).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    use crate::backend::StreamBackend;
+    use crate::event::ResizeEvent;
+    use crate::font::{Color, Profile, Style, NAVY};
+    use crate::{foreign, Command, Message, Model, Program};
+
+    /// Color of the label, which depends on the background.
+    const LABEL: Color = Color::adaptive(NAVY, Color::Rgb(255, 135, 0));
+
+    /// Shows the size of the window, and exits on `q`.
+    struct Size(u16, u16);
+
+    impl Model for Size {
+        fn update(&mut self, message: &Message) -> Option<Command> {
+            if let Some(event) = message.downcast_ref::<KeyEvent>() {
+                if event.code == KeyCode::Char('q') {
+                    return Some(Box::new(crate::exit));
+                }
+            }
+            if let Some(event) = message.downcast_ref::<ResizeEvent>() {
+                *self = Size(event.0, event.1);
+            }
+
+            None
+        }
+
+        fn view(&self) -> String {
+            let label = Style::new().foreground(LABEL).render("size");
+            format!("{} {}x{}", label, self.0, self.1)
+        }
+    }
+
+    /// Time to wait for the expected output before failing.
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Read from the file until the output contains the text.
+    fn read_until(file: &mut File, output: &mut String, text: &str) {
+        let deadline = Instant::now() + TIMEOUT;
+        let mut buffer = [0; 1024];
+        while !output.contains(text) {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let ready = unsafe {
+                foreign::poll_read(&[file.as_raw_fd()], timeout.as_millis() as libc::c_int)
+            }
+            .unwrap();
+            assert!(ready[0], "timed out before {:?} in {:?}", text, output);
+
+            let len = file.read(&mut buffer).unwrap();
+            assert!(len > 0, "closed before {:?} in {:?}", text, output);
+            output.push_str(&String::from_utf8_lossy(&buffer[..len]));
+        }
+    }
+
+    #[test]
+    fn test_stream_pty() {
+        // Run two programs at once, each on its own pseudoterminal, with its
+        // own background.
+        let programs: Vec<_> = (0..2)
+            .map(|i| {
+                let (master, slave) = unsafe { foreign::open_pty() }.unwrap();
+                unsafe { foreign::enable_raw_mode(slave.as_raw_fd()) }.unwrap();
+
+                let (resize_tx, resize_rx) = std::sync::mpsc::channel();
+                let input = slave.try_clone().unwrap();
+                let program = std::thread::spawn(move || {
+                    let backend = StreamBackend::new(input, slave, (80, 24 + i), resize_rx);
+                    Program::new(Size(80, 24 + i))
+                        .with_backend(backend)
+                        .with_profile(Profile::Ansi256)
+                        .with_dark_background(i == 1)
+                        .run()
+                });
+
+                (master, resize_tx, program, i)
+            })
+            .collect();
+
+        for (mut master, resize_tx, program, i) in programs {
+            let label = ["\x1B[34msize\x1B[0m", "\x1B[38;5;208msize\x1B[0m"][i as usize];
+
+            let mut output = String::new();
+            read_until(
+                &mut master,
+                &mut output,
+                &format!("{} 80x{}", label, 24 + i),
+            );
+
+            resize_tx.send((100, 40)).unwrap();
+            read_until(&mut master, &mut output, &format!("{} 100x40", label));
+
+            master.write_all(b"q").unwrap();
+            program.join().unwrap().unwrap();
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::Write;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::Event;
+
+use crate::backend::{Backend, EventSource};
+
+/// Backend that keeps the output in memory, and delivers events sent
+/// from a test.
+///
+/// There are no more events once every sender returned by `sender` has been
+/// dropped, which leaves the program waiting for commands to finish.
+///
+/// # Examples
+///
+/// ```
+/// use locket::backend::TestBackend;
+/// use locket::crossterm::event::{Event, KeyCode, KeyEvent};
+/// use locket::{Command, Message, Model, Program};
+///
+/// struct Counter(usize);
+///
+/// impl Model for Counter {
+///     fn update(&mut self, message: &Message) -> Option<Command> {
+///         let event = message.downcast_ref::<KeyEvent>()?;
+///         match event.code {
+///             KeyCode::Char('q') => Some(Box::new(locket::exit)),
+///             _ => {
+///                 self.0 += 1;
+///                 None
+///             }
+///         }
+///     }
+///
+///     fn view(&self) -> String {
+///         format!("count: {}", self.0)
+///     }
+/// }
+///
+/// let backend = TestBackend::new(80, 24);
+/// let tx = backend.sender();
+/// let output = backend.output();
+///
+/// for c in ['a', 'b', 'q'] {
+///     tx.send(Event::Key(KeyEvent::from(KeyCode::Char(c)))).unwrap();
+/// }
+///
+/// Program::new(Counter(0)).with_backend(backend).run().unwrap();
+///
+/// let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+/// assert!(output.contains("count: 2"));
+/// ```
+pub struct TestBackend {
+    /// The (x, y) size of the terminal.
+    size: (u16, u16),
+    /// Everything written to the backend.
+    output: Arc<Mutex<Vec<u8>>>,
+    /// Sender kept until `events` is called, so that `sender` can be cloned.
+    tx: Option<Sender<Event>>,
+    /// Receiver taken by `events`.
+    rx: Option<Receiver<Event>>,
+    /// True while in raw mode.
+    raw: Arc<Mutex<bool>>,
+}
+
+impl TestBackend {
+    /// Return a new instance of `TestBackend` with the (x, y) size.
+    pub fn new(x: u16, y: u16) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        Self {
+            size: (x, y),
+            output: Arc::new(Mutex::new(Vec::new())),
+            tx: Some(tx),
+            rx: Some(rx),
+            raw: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    /// Return a sender for events delivered to the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after the program has started.
+    pub fn sender(&self) -> Sender<Event> {
+        self.tx
+            .clone()
+            .expect("sender must be taken before the program starts")
+    }
+
+    /// Return everything that will be written to the backend.
+    pub fn output(&self) -> Arc<Mutex<Vec<u8>>> {
+        self.output.clone()
+    }
+
+    /// Return a handle that reports whether the backend is in raw mode.
+    pub fn raw_mode(&self) -> Arc<Mutex<bool>> {
+        self.raw.clone()
+    }
+}
+
+impl Write for TestBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&self) -> std::io::Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn events(&mut self) -> std::io::Result<Box<dyn EventSource>> {
+        self.tx = None;
+
+        let rx = self
+            .rx
+            .take()
+            .ok_or_else(|| std::io::Error::other("events can only be taken from a backend once"))?;
+
+        Ok(Box::new(ChannelEvents(rx)))
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        *self.raw.lock().unwrap() = true;
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        *self.raw.lock().unwrap() = false;
+        Ok(())
+    }
+}
+
+/// Delivers the events received on a channel.
+struct ChannelEvents(Receiver<Event>);
+
+impl EventSource for ChannelEvents {
+    fn read(&mut self) -> std::io::Result<Option<Event>> {
+        Ok(self.0.recv().ok())
+    }
+}
@@ -0,0 +1,491 @@
+use std::fmt::Write;
+
+use crate::{
+    ansi,
+    font::{Color, Profile},
+};
+
+/// Symbol of the cell following a wide character, which is covered by it.
+pub const CONTINUATION: char = '\0';
+
+/// A set of text attributes, such as bold or underline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// No attributes.
+    pub const NONE: Modifiers = Modifiers(0);
+    /// Bold text.
+    pub const BOLD: Modifiers = Modifiers(1);
+    /// Faint text.
+    pub const FAINT: Modifiers = Modifiers(1 << 1);
+    /// Italic text.
+    pub const ITALIC: Modifiers = Modifiers(1 << 2);
+    /// Underlined text.
+    pub const UNDERLINE: Modifiers = Modifiers(1 << 3);
+    /// Text with foreground and background colors swapped.
+    pub const INVERSE: Modifiers = Modifiers(1 << 4);
+    /// Text with a line through it.
+    pub const STRIKETHROUGH: Modifiers = Modifiers(1 << 5);
+
+    /// Every attribute, paired with the parameter that enables it.
+    const CODES: [(Modifiers, u8); 6] = [
+        (Modifiers::BOLD, 1),
+        (Modifiers::FAINT, 2),
+        (Modifiers::ITALIC, 3),
+        (Modifiers::UNDERLINE, 4),
+        (Modifiers::INVERSE, 7),
+        (Modifiers::STRIKETHROUGH, 9),
+    ];
+
+    /// Return true if all of the attributes in `other` are set.
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set the attributes in `other`.
+    pub fn insert(&mut self, other: Modifiers) {
+        self.0 |= other.0;
+    }
+
+    /// Unset the attributes in `other`.
+    pub fn remove(&mut self, other: Modifiers) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Modifiers {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+/// A single character on the screen, and its style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character drawn in the cell.
+    ///
+    /// This is `CONTINUATION` when the cell is covered by a wide character
+    /// in the cell before it.
+    pub symbol: char,
+    /// The color of the text, or the terminal default.
+    pub foreground: Option<Color>,
+    /// The color behind the text, or the terminal default.
+    pub background: Option<Color>,
+    /// Text attributes.
+    pub modifiers: Modifiers,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            foreground: None,
+            background: None,
+            modifiers: Modifiers::NONE,
+        }
+    }
+}
+
+impl Cell {
+    /// Return a new instance of `Cell` with the default style.
+    pub fn new(symbol: char) -> Self {
+        Self {
+            symbol,
+            ..Default::default()
+        }
+    }
+
+    /// Return true if the cells have the same style, ignoring the symbol.
+    fn same_style(&self, other: &Cell) -> bool {
+        self.foreground == other.foreground
+            && self.background == other.background
+            && self.modifiers == other.modifiers
+    }
+
+    /// Apply the parameters of a Select Graphic Rendition sequence.
+    fn apply_sgr(&mut self, params: &str) {
+        let mut params = params
+            .split(';')
+            .map(|p| p.parse::<u16>().unwrap_or(0))
+            .peekable();
+
+        // An empty sequence is a reset.
+        if params.peek().is_none() {
+            *self = Cell::new(self.symbol);
+        }
+
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Cell::new(self.symbol),
+                1 => self.modifiers.insert(Modifiers::BOLD),
+                2 => self.modifiers.insert(Modifiers::FAINT),
+                3 => self.modifiers.insert(Modifiers::ITALIC),
+                4 => self.modifiers.insert(Modifiers::UNDERLINE),
+                7 => self.modifiers.insert(Modifiers::INVERSE),
+                9 => self.modifiers.insert(Modifiers::STRIKETHROUGH),
+                22 => self.modifiers.remove(Modifiers::BOLD | Modifiers::FAINT),
+                23 => self.modifiers.remove(Modifiers::ITALIC),
+                24 => self.modifiers.remove(Modifiers::UNDERLINE),
+                27 => self.modifiers.remove(Modifiers::INVERSE),
+                29 => self.modifiers.remove(Modifiers::STRIKETHROUGH),
+                30..=37 => self.foreground = Some(Color::Ansi((param - 30) as u8)),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(Color::Ansi((param - 40) as u8)),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(Color::Ansi((param - 82) as u8)),
+                100..=107 => self.background = Some(Color::Ansi((param - 92) as u8)),
+                38 | 48 => {
+                    let color = match params.next() {
+                        Some(5) => params.next().map(|n| Color::Indexed(n as u8)),
+                        Some(2) => {
+                            let mut component = || params.next().unwrap_or(0) as u8;
+                            Some(Color::Rgb(component(), component(), component()))
+                        }
+                        _ => None,
+                    };
+
+                    if param == 38 {
+                        self.foreground = color;
+                    } else {
+                        self.background = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Write the sequence that changes the style from `prev` to this cell's style.
+    fn write_sgr(&self, prev: &Cell, profile: Profile, out: &mut String) {
+        let mut params = Vec::new();
+
+        // Colors are downsampled to the profile before comparing.
+        let downsample = |cell: &Cell| Cell {
+            foreground: cell.foreground.and_then(|c| c.downsample(profile)),
+            background: cell.background.and_then(|c| c.downsample(profile)),
+            ..*cell
+        };
+        let (this, prev) = (downsample(self), downsample(prev));
+
+        // There is no single parameter to unset every attribute, so reset
+        // everything when any attribute is removed.
+        let removed = Modifiers::CODES
+            .iter()
+            .any(|(m, _)| prev.modifiers.contains(*m) && !this.modifiers.contains(*m));
+        let reset = removed
+            || (prev.foreground.is_some() && this.foreground.is_none())
+            || (prev.background.is_some() && this.background.is_none());
+
+        let prev = if reset {
+            params.push("0".to_string());
+            Cell::default()
+        } else {
+            prev
+        };
+
+        for (modifier, code) in Modifiers::CODES {
+            if this.modifiers.contains(modifier) && !prev.modifiers.contains(modifier) {
+                params.push(code.to_string());
+            }
+        }
+        if let (Some(color), true) = (this.foreground, this.foreground != prev.foreground) {
+            params.push(color.foreground_code());
+        }
+        if let (Some(color), true) = (this.background, this.background != prev.background) {
+            params.push(color.background_code());
+        }
+
+        if !params.is_empty() {
+            write!(out, "\x1B[{}m", params.join(";")).unwrap();
+        }
+    }
+}
+
+/// A two dimensional grid of cells that a model may draw into.
+///
+/// When executing with `Program::with_cells`, each frame is drawn into a new
+/// buffer and compared with the last, so only the cells that changed are
+/// written to the terminal.
+///
+/// # Examples
+///
+/// ```
+/// use locket::buffer::{Buffer, Cell};
+///
+/// let mut buffer = Buffer::new(10, 2);
+///
+/// buffer.set_string(0, 0, "Hello", Cell::default());
+/// buffer.set_ansi(0, 1, "\x1B[1mWorld\x1B[0m");
+///
+/// assert_eq!(buffer.get(1, 0).unwrap().symbol, 'e');
+/// assert!(buffer.get(0, 1).unwrap().modifiers.contains(locket::buffer::Modifiers::BOLD));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Buffer {
+    /// Number of columns.
+    width: u16,
+    /// Number of rows.
+    height: u16,
+    /// Cells in row-major order.
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// Return a new instance of `Buffer` filled with empty cells.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); usize::from(width) * usize::from(height)],
+        }
+    }
+
+    /// Return the number of columns.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the number of rows.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Return the cell at the position, if it is within the buffer.
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Return the cell at the position mutably, if it is within the buffer.
+    pub fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        self.index(x, y).map(|i| &mut self.cells[i])
+    }
+
+    /// Reset every cell to an empty cell.
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    /// Write text into a single row, starting at the position.
+    ///
+    /// Each character takes the style of `style`, while the symbol of `style`
+    /// is ignored. Text beyond the edge of the buffer is clipped, and zero width
+    /// characters are skipped.
+    ///
+    /// Returns the column after the last character written.
+    pub fn set_string<T>(&mut self, x: u16, y: u16, text: T, style: Cell) -> u16
+    where
+        T: AsRef<str>,
+    {
+        let mut x = x;
+        for c in text.as_ref().chars() {
+            x = self.set_char(x, y, c, style);
+        }
+
+        x
+    }
+
+    /// Write text that may contain escape sequences, starting at the position.
+    ///
+    /// Styles set by SGR sequences, such as those produced by the `font` module,
+    /// are applied to the cells. Other escape sequences are ignored. Each line
+    /// is written to the next row, starting from the same column.
+    ///
+    /// 24-bit colors are kept as `Color::Rgb`.
+    pub fn set_ansi<T>(&mut self, x: u16, y: u16, text: T)
+    where
+        T: AsRef<str>,
+    {
+        let mut style = Cell::default();
+
+        for (row, line) in text.as_ref().split('\n').enumerate() {
+            let Some(y) = u16::try_from(row).ok().and_then(|row| y.checked_add(row)) else {
+                return;
+            };
+
+            let mut col = x;
+            let mut i = 0;
+            while i < line.len() {
+                let len = ansi::escape_len(&line[i..]);
+                if len > 0 {
+                    let sequence = &line[i..i + len];
+                    if let Some(params) = sequence
+                        .strip_prefix("\x1B[")
+                        .and_then(|s| s.strip_suffix('m'))
+                    {
+                        style.apply_sgr(params);
+                    }
+
+                    i += len;
+                    continue;
+                }
+
+                let c = line[i..].chars().next().unwrap();
+                i += c.len_utf8();
+
+                if c != '\r' {
+                    col = self.set_char(col, y, c, style);
+                }
+            }
+        }
+    }
+
+    /// Write a single character, returning the column after it.
+    fn set_char(&mut self, x: u16, y: u16, c: char, style: Cell) -> u16 {
+        let width = ansi::char_width(c);
+        if width == 0 {
+            return x;
+        }
+
+        // A wide character that does not fit is not drawn.
+        if x.saturating_add(width as u16) > self.width {
+            return self.width;
+        }
+
+        // Remove what is left of any wide character that is partly overwritten.
+        if self.get(x, y).map(|cell| cell.symbol) == Some(CONTINUATION) && x > 0 {
+            if let Some(cell) = self.get_mut(x - 1, y) {
+                cell.symbol = ' ';
+            }
+        }
+        let end = x + width as u16;
+        if self.get(end, y).map(|cell| cell.symbol) == Some(CONTINUATION) {
+            if let Some(cell) = self.get_mut(end, y) {
+                cell.symbol = ' ';
+            }
+        }
+
+        if let Some(cell) = self.get_mut(x, y) {
+            *cell = Cell { symbol: c, ..style };
+        }
+        if width == 2 {
+            if let Some(cell) = self.get_mut(x + 1, y) {
+                *cell = Cell {
+                    symbol: CONTINUATION,
+                    ..style
+                };
+            }
+        }
+
+        end
+    }
+
+    /// Return the index of the position within `cells`.
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(usize::from(y) * usize::from(self.width) + usize::from(x))
+    }
+
+    /// Return the output that updates the screen from `prev` to this buffer.
+    ///
+    /// The screen is assumed to show `prev` with its top left corner in the top
+    /// left corner of the terminal. If the buffers are not the same size, the
+    /// screen is cleared and drawn again. Colors are downsampled to the profile.
+    pub(crate) fn diff(&self, prev: &Buffer, profile: Profile) -> String {
+        let mut out = String::new();
+
+        let full = self.width != prev.width || self.height != prev.height;
+        if full {
+            out.push_str("\x1B[0m\x1B[2J");
+        }
+
+        let mut style = Cell::default();
+        let mut cursor: Option<(u16, u16)> = None;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self.get(x, y).unwrap();
+                if cell.symbol == CONTINUATION || (!full && prev.get(x, y) == Some(cell)) {
+                    continue;
+                }
+
+                if cursor != Some((x, y)) {
+                    write!(out, "\x1B[{};{}H", y + 1, x + 1).unwrap();
+                }
+
+                cell.write_sgr(&style, profile, &mut out);
+                style = *cell;
+
+                out.push(cell.symbol);
+                cursor = Some((x + ansi::char_width(cell.symbol).max(1) as u16, y));
+            }
+        }
+
+        if !style.same_style(&Cell::default()) {
+            out.push_str("\x1B[0m");
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::{Buffer, Cell, Modifiers, CONTINUATION};
+    use crate::font::{Color, Profile};
+
+    #[test]
+    fn test_set_ansi() {
+        let mut buffer = Buffer::new(4, 2);
+        buffer.set_ansi(1, 0, "a\x1B[1;38;5;9mb\x1B[0;48;2;1;2;3mc\n日x");
+
+        assert_eq!(buffer.get(1, 0).unwrap(), &Cell::new('a'));
+        assert_eq!(
+            buffer.get(2, 0).unwrap(),
+            &Cell {
+                symbol: 'b',
+                foreground: Some(Color::Indexed(9)),
+                background: None,
+                modifiers: Modifiers::BOLD,
+            }
+        );
+        assert_eq!(
+            buffer.get(3, 0).unwrap(),
+            &Cell {
+                background: Some(Color::Rgb(1, 2, 3)),
+                ..Cell::new('c')
+            }
+        );
+
+        assert_eq!(buffer.get(1, 1).unwrap().symbol, '日');
+        assert_eq!(buffer.get(2, 1).unwrap().symbol, CONTINUATION);
+        assert_eq!(buffer.get(3, 1).unwrap().symbol, 'x');
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut prev = Buffer::new(5, 2);
+        prev.set_string(0, 0, "hello", Cell::default());
+        prev.set_string(0, 1, "world", Cell::default());
+
+        let mut next = prev.clone();
+        next.set_string(1, 0, "EL", Cell::default());
+        next.set_string(
+            4,
+            1,
+            "D",
+            Cell {
+                foreground: Some(Color::Indexed(1)),
+                ..Default::default()
+            },
+        );
+
+        // Only the changed cells are written, with a single cursor move
+        // for consecutive cells.
+        assert_eq!(
+            next.diff(&prev, Profile::TrueColor),
+            "\x1B[1;2HEL\x1B[2;5H\x1B[38;5;1mD\x1B[0m"
+        );
+        assert_eq!(
+            next.diff(&prev, Profile::Ansi),
+            "\x1B[1;2HEL\x1B[2;5H\x1B[31mD\x1B[0m"
+        );
+
+        assert_eq!(next.diff(&next, Profile::TrueColor), "");
+    }
+}
@@ -2,7 +2,12 @@ use std::fmt::Write;
 
 use crossterm::event::{KeyCode, KeyEvent};
 
-use crate::{font::foreground, Command, Message, Model, Pager};
+use crate::{
+    ansi,
+    font::{foreground, Color, MAROON, RED},
+    layout::Rect,
+    Command, Message, Model, Pager,
+};
 
 pub struct List {
     /// Height of the list.
@@ -13,9 +18,11 @@ pub struct List {
     height: usize,
     /// Index of the cursor over the current page.
     position: usize,
+    /// Width of the list, if items longer than it are truncated.
+    width: Option<usize>,
 
     /// The text color of the selected item.
-    foreground: u8,
+    foreground: Color,
 
     /// Items visible in the list.
     items: Vec<String>,
@@ -25,9 +32,14 @@ pub struct List {
 }
 
 impl List {
-    pub fn new<T>(items: T, height: usize, foreground: u8) -> Self
+    /// A color for the selected item that is readable on both light and
+    /// dark backgrounds.
+    pub const HIGHLIGHT: Color = Color::adaptive(MAROON, RED);
+
+    pub fn new<T, C>(items: T, height: usize, foreground: C) -> Self
     where
         T: Iterator<Item = String>,
+        C: Into<Color>,
     {
         let items: Vec<String> = items.collect();
         let items_len = items.len();
@@ -35,7 +47,8 @@ impl List {
         Self {
             height,
             position: 0,
-            foreground,
+            width: None,
+            foreground: foreground.into(),
             items,
             pager: Pager::new(0, height, items_len / height),
         }
@@ -52,8 +65,49 @@ impl List {
     }
 
     /// Set the height of the list.
+    ///
+    /// The pages are measured again, keeping the cursor on the same item.
     pub fn set_height(&mut self, height: usize) {
-        self.height = height;
+        let overall = self.get_overall_position();
+
+        self.height = height.max(1);
+        self.pager.set_per(self.height);
+        self.pager.set_total_by_len(self.items.len());
+        self.pager.set_current(overall / self.height);
+        self.position = overall % self.height;
+    }
+
+    /// Set the height and width of the list to fit the area.
+    ///
+    /// # Examples
+    ///
+    /// Give the list the space below a title:
+    ///
+    /// ```
+    /// use locket::components::List;
+    /// use locket::event::ResizeEvent;
+    /// use locket::layout::{Constraint, Layout};
+    /// use locket::Model;
+    ///
+    /// let mut list = List::new((0..20).map(|i| i.to_string()), 5, List::HIGHLIGHT);
+    ///
+    /// let resize = ResizeEvent(40, 12);
+    /// let areas = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+    ///     .split(resize.area());
+    /// list.set_area(areas[1]);
+    ///
+    /// assert_eq!(list.view().lines().count(), 11);
+    /// ```
+    pub fn set_area(&mut self, area: Rect) {
+        self.set_height(area.height as usize);
+        self.set_width(area.width as usize);
+    }
+
+    /// Set the width of the list.
+    ///
+    /// Items that are wider are truncated, ending with an ellipsis.
+    pub fn set_width(&mut self, width: usize) {
+        self.width = Some(width);
     }
 
     /// Move the cursor up.
@@ -202,8 +256,7 @@ impl List {
     pub fn get_index_last_item(&self) -> usize {
         self.pager
             .get_num_on_page(self.items.len())
-            .checked_sub(1)
-            .unwrap_or(0)
+            .saturating_sub(1)
     }
 
     /// Set the position
@@ -279,8 +332,8 @@ impl Model for List {
         // On `ResizeEvent`:
         //
         // This component is likely to be used alongside other components.
-        // It is the parent model's job to keep the height of the list in
-        // check via the `set_height` method.
+        // It is the parent model's job to give the list its area, split from
+        // the terminal with a `Layout`, via the `set_area` method.
         //
 
         None
@@ -309,11 +362,15 @@ impl Model for List {
 
         let mut buffer = String::new();
 
-        for (index, item) in selected.into_iter().enumerate() {
+        for (index, item) in selected.iter().enumerate() {
+            let item = match self.width {
+                Some(width) => ansi::truncate(item, width, "…"),
+                None => item.to_string(),
+            };
             let string = if self.position == index {
-                format!("{}", foreground(item, self.foreground))
+                foreground(item, self.foreground)
             } else {
-                format!("{item}")
+                item
             };
             write!(buffer, "{string}").unwrap();
 
@@ -327,7 +384,7 @@ impl Model for List {
         let buffer_height = buffer.lines().count();
         let remaining_height = available_height - buffer_height;
 
-        let padding: String = std::iter::repeat("\r\n").take(remaining_height).collect();
+        let padding = "\r\n".repeat(remaining_height);
 
         format!("{buffer}{padding}")
     }
@@ -2,7 +2,8 @@ use std::fmt::Write;
 
 use crate::crossterm::event::{KeyCode, KeyEvent};
 use crate::{
-    font::{fill_background, MAROON, WHITE},
+    ansi,
+    font::{fill_background, Color, MAROON, WHITE},
     Command, Message, Model,
 };
 
@@ -12,12 +13,15 @@ use crate::{
 pub struct TextInput {
     /// Internal buffer containing the text in the field.
     buffer: String,
-    /// Position of cursor within the buffer.
+    /// Position of cursor within the buffer, in bytes.
+    ///
+    /// This is always at the start of a grapheme, so that the cursor never
+    /// splits a character from its accents.
     position: usize,
     /// The font color of the character under the cursor.
-    fill: u8,
+    fill: Color,
     /// The background color of the cursor.
-    background: u8,
+    background: Color,
 }
 
 impl Default for TextInput {
@@ -56,11 +60,12 @@ impl Model for TextInput {
         let mut buffer = String::with_capacity(self.buffer.len());
         buffer.write_str(&self.buffer[..self.position]).unwrap();
         if self.position < self.buffer.len() {
-            let cursor_char: String = self.buffer[self.position..=self.position].to_string();
+            let end = self.next_position();
+            let cursor_char = &self.buffer[self.position..end];
             buffer
                 .write_str(&fill_background(cursor_char, self.fill, self.background))
                 .unwrap();
-            buffer.write_str(&self.buffer[self.position + 1..]).unwrap();
+            buffer.write_str(&self.buffer[end..]).unwrap();
         } else {
             buffer
                 .write_str(&fill_background(" ", self.fill, self.background))
@@ -73,12 +78,16 @@ impl Model for TextInput {
 
 impl TextInput {
     /// Return a new instance of `Input`.
-    pub fn new(fill: u8, background: u8) -> Self {
+    pub fn new<F, B>(fill: F, background: B) -> Self
+    where
+        F: Into<Color>,
+        B: Into<Color>,
+    {
         Self {
             buffer: String::new(),
             position: 0,
-            fill,
-            background,
+            fill: fill.into(),
+            background: background.into(),
         }
     }
 
@@ -109,41 +118,117 @@ impl TextInput {
         self.buffer = as_string;
     }
 
-    /// Return the current position within the buffer.
+    /// Return the current position within the buffer, in bytes.
     pub fn position(&self) -> usize {
         self.position
     }
 
-    /// Set the position.
+    /// Set the position, in bytes.
+    ///
+    /// A position within a grapheme is moved to its start.
     pub fn set_position(&mut self, pos: usize) {
-        self.position = pos;
+        if pos >= self.buffer.len() {
+            self.position = self.buffer.len();
+            return;
+        }
+
+        let start = self.graphemes().take_while(|start| *start <= pos).last();
+        self.position = start.unwrap_or(0);
+    }
+
+    /// Return the start of each grapheme in the buffer.
+    fn graphemes(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut start = 0;
+
+        std::iter::from_fn(move || {
+            if start >= self.buffer.len() {
+                return None;
+            }
+
+            let current = start;
+            start += ansi::grapheme_len(&self.buffer[start..]);
+            Some(current)
+        })
+    }
+
+    /// Return the position after the grapheme under the cursor.
+    fn next_position(&self) -> usize {
+        self.position + ansi::grapheme_len(&self.buffer[self.position..])
     }
 
-    /// Delete one character behind the current position, and move the cursor back.
+    /// Return the position of the grapheme before the cursor.
+    fn previous_position(&self) -> usize {
+        self.graphemes()
+            .take_while(|start| *start < self.position)
+            .last()
+            .unwrap_or(0)
+    }
+
+    /// Delete one grapheme behind the current position, and move the cursor back.
     fn handle_backspace(&mut self) {
         if self.position > 0 {
-            self.buffer.remove(self.position - 1);
-            self.position -= 1;
+            let previous = self.previous_position();
+            self.buffer.replace_range(previous..self.position, "");
+            self.position = previous;
         }
     }
 
     /// Insert the character at the current position, and move the cursor forward.
+    ///
+    /// A character that joins the grapheme before it, such as an accent,
+    /// leaves the cursor after that grapheme.
     fn handle_char(&mut self, c: char) {
         self.buffer.insert(self.position, c);
-        self.position += 1;
+
+        let end = self.position + c.len_utf8();
+        let next = self.graphemes().find(|start| *start >= end);
+        self.position = next.unwrap_or(self.buffer.len());
     }
 
     /// Move the cursor to the left, if possible.
     fn handle_left(&mut self) {
         if self.position > 0 {
-            self.position -= 1;
+            self.position = self.previous_position();
         }
     }
 
     /// Move the cursor to the right, if possible.
     fn handle_right(&mut self) {
         if self.position < self.buffer.len() {
-            self.position += 1;
+            self.position = self.next_position();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::components::TextInput;
+    use crate::{Message, Model};
+
+    fn press(input: &mut TextInput, code: KeyCode) {
+        let message: Message = Box::new(KeyEvent::new(code, KeyModifiers::NONE));
+        input.update(&message);
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let mut input = TextInput::default();
+        for c in ['日', 'e', '\u{301}', 'x'] {
+            press(&mut input, KeyCode::Char(c));
         }
+        assert_eq!(input.position(), input.buffer().len());
+
+        // The accent moves and is deleted along with its letter.
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.position(), "日".len());
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.buffer(), "日x");
+
+        input.set_position(1);
+        assert_eq!(input.position(), 0);
     }
 }
@@ -1,69 +1,76 @@
-/// Move the cursor.
+//! The terminal cursor shown by a model.
+//!
+//! The cursor is hidden unless `Model::cursor` returns where it should be
+//! shown, in which case it is placed after each frame is drawn.
+
+/// Describes the shape of the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shape {
+    /// A block covering the whole cell.
+    #[default]
+    Block,
+    /// A vertical bar at the left of the cell.
+    Bar,
+    /// A line under the cell.
+    Underline,
+}
+
+/// Describes where the cursor is shown, and how it looks.
 ///
-/// You may prefer one of the "move_*" macros instead.
+/// # Examples
 ///
-/// Directions are represented by "A", "B", "C", or "D" as described
-/// by ANSI/VT100 Terminal Control Escape Sequences:
+/// Show a blinking bar after a prompt:
 ///
-/// https://espterm.github.io/docs/VT100%20escape%20codes.html
-#[macro_export]
-macro_rules! move_cursor {
-    ($direction:literal) => {
-        print!("\x1B[{}", $direction);
-    };
-    ($direction:literal, $num:expr) => {
-        print!("\x1B[{}{}", $num, $direction);
-    };
-}
-
-/// Move the cursor up.
+/// ```
+/// use locket::cursor::{Cursor, Shape};
 ///
-/// If the number of steps is not provided, the default of 1 is used.
-#[macro_export]
-macro_rules! move_up {
-    () => {
-        locket::move_cursor!("A");
-    };
-    ($num:expr) => {
-        locket::move_cursor!("A", $num);
-    };
+/// let prompt = "Name: ";
+/// let cursor = Cursor::new(prompt.len() as u16, 0).with_shape(Shape::Bar);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// The column, counted from the left of the view.
+    pub x: u16,
+    /// The line, counted from the top of the view.
+    pub y: u16,
+    /// The shape of the cursor.
+    pub shape: Shape,
+    /// True if the cursor blinks.
+    pub blink: bool,
 }
 
-/// Move the cursor down.
-///
-/// If the number of steps is not provided, the default of 1 is used.
-#[macro_export]
-macro_rules! move_down {
-    () => {
-        locket::move_cursor!("B");
-    };
-    ($num:expr) => {
-        locket::move_cursor!("B", $num);
-    };
-}
+impl Cursor {
+    /// Return a new instance of `Cursor` at the position.
+    ///
+    /// The cursor is a blinking block.
+    pub fn new(x: u16, y: u16) -> Self {
+        Self {
+            x,
+            y,
+            shape: Shape::default(),
+            blink: true,
+        }
+    }
 
-/// Move the cursor right.
-///
-/// If the number of steps is not provided, the default of 1 is used.
-#[macro_export]
-macro_rules! move_right {
-    () => {
-        locket::move_cursor!("C");
-    };
-    ($num:expr) => {
-        locket::move_cursor!("C", $num);
-    };
-}
+    /// Set the shape of the cursor.
+    pub fn with_shape(self, shape: Shape) -> Self {
+        Self { shape, ..self }
+    }
 
-/// Move the cursor left.
-///
-/// If the number of steps is not provided, the default of 1 is used.
-#[macro_export]
-macro_rules! move_left {
-    () => {
-        locket::move_cursor!("D");
-    };
-    ($num:expr) => {
-        locket::move_cursor!("D", $num);
-    };
+    /// Set whether the cursor blinks.
+    pub fn with_blink(self, blink: bool) -> Self {
+        Self { blink, ..self }
+    }
+
+    /// Return the sequence that sets the shape of the cursor.
+    pub(crate) fn style(&self) -> String {
+        let code = match self.shape {
+            Shape::Block => 1,
+            Shape::Underline => 3,
+            Shape::Bar => 5,
+        };
+
+        // Each steady shape follows the blinking one.
+        format!("\x1B[{} q", code + u8::from(!self.blink))
+    }
 }
@@ -7,6 +7,7 @@ pub enum Error {
     IO(std::io::Error),
 
     /// An error occurred during FFI.
+    #[allow(clippy::upper_case_acronyms)]
     FFI(&'static str),
 }
 
@@ -24,3 +25,12 @@ impl From<std::io::Error> for Error {
         Error::IO(value)
     }
 }
+
+impl From<Error> for std::io::Error {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::IO(error) => error,
+            Error::FFI(reason) => std::io::Error::other(reason),
+        }
+    }
+}
@@ -1,4 +1,22 @@
+use crate::layout::Rect;
+
 /// Event representing a terminal resize.
 ///
-/// Contains the new x and y size.
+/// Contains the new x and y size. Also delivered once when the program
+/// starts on a terminal, with its initial size.
 pub struct ResizeEvent(pub u16, pub u16);
+
+impl ResizeEvent {
+    /// Return the area of the whole terminal.
+    pub fn area(&self) -> Rect {
+        Rect::new(0, 0, self.0, self.1)
+    }
+}
+
+/// Event representing a line read from piped standard input.
+///
+/// Delivered when the program is configured with `Program::with_stdin`.
+pub struct StdinEvent(pub String);
+
+/// Event representing the end of piped standard input.
+pub struct StdinClosedEvent;
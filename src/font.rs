@@ -1,241 +1,134 @@
-/// `Black` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const BLACK: u8 = 0;
+pub use self::background::has_dark_background;
+pub(crate) use self::background::{detect_background, parse_background, BackgroundScope};
+pub use self::border::Border;
+pub use self::color::{AdaptiveColor, Color, ParseColorError};
+pub use self::profile::Profile;
+pub use self::style::{Align, Style};
 
-/// `Maroon` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const MAROON: u8 = 1;
+mod background;
+mod border;
+mod color;
+mod profile;
+mod style;
 
-/// `Green` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Black`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const GREEN: u8 = 2;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const BLACK: Color = Color::Ansi(0);
 
-/// `Olive` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Maroon`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const OLIVE: u8 = 3;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const MAROON: Color = Color::Ansi(1);
 
-/// `Navy` XTERM 256.
+/// `Green`, one of the 16 standard colors.
 ///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const NAVY: u8 = 4;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const GREEN: Color = Color::Ansi(2);
 
-/// `Purple` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Olive`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const PURPLE: u8 = 5;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const OLIVE: Color = Color::Ansi(3);
 
-/// `Teal` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Navy`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const TEAL: u8 = 6;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const NAVY: Color = Color::Ansi(4);
 
-/// `Silver` XTERM 256.
+/// `Purple`, one of the 16 standard colors.
 ///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const SILVER: u8 = 7;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const PURPLE: Color = Color::Ansi(5);
 
-/// `Gray` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Teal`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const GRAY: u8 = 8;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const TEAL: Color = Color::Ansi(6);
 
-/// `Red` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Silver`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const RED: u8 = 9;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const SILVER: Color = Color::Ansi(7);
 
-/// `Lime` XTERM 256.
+/// `Gray`, one of the 16 standard colors.
 ///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const LIME: u8 = 10;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const GRAY: Color = Color::Ansi(8);
 
-/// `Yellow` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Red`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const YELLOW: u8 = 11;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const RED: Color = Color::Ansi(9);
 
-/// `Blue` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Lime`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const BLUE: u8 = 12;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const LIME: Color = Color::Ansi(10);
 
-/// `Fuchsia` XTERM 256.
+/// `Yellow`, one of the 16 standard colors.
 ///
-/// More colors are available, but not included as a const in Locket.
-///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const FUCHSIA: u8 = 13;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const YELLOW: Color = Color::Ansi(11);
 
-/// `Aqua` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Blue`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const AQUA: u8 = 14;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const BLUE: Color = Color::Ansi(12);
 
-/// `White` XTERM 256.
-///
-/// More colors are available, but not included as a const in Locket.
+/// `Fuchsia`, one of the 16 standard colors.
 ///
-/// https://en.wikipedia.org/wiki/Xterm
-pub const WHITE: u8 = 15;
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const FUCHSIA: Color = Color::Ansi(13);
 
-/// Decorate a string with styles.
+/// `Aqua`, one of the 16 standard colors.
 ///
-/// # Examples
-///
-/// Create a bold, underlined maroon greeting:
-///
-/// ```
-/// locket::font::FontBuilder::new("Hello, World!")
-///     .bold()
-///     .underline()
-///     .foreground(locket::font::MAROON)
-///     .to_string();
-/// ```
-pub struct FontBuilder {
-    buffer: String,
-}
-
-impl FontBuilder {
-    /// Return a new instance of FontBuilder.
-    pub fn new<T>(text: T) -> Self
-    where
-        T: AsRef<str>,
-    {
-        Self {
-            buffer: format!("{}{}", text.as_ref(), "\x1B[0m"),
-        }
-    }
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const AQUA: Color = Color::Ansi(14);
 
-    /// Apply a bold sequence.
-    pub fn bold(self) -> Self {
-        Self {
-            buffer: format!("\x1B[1m{}", self.buffer),
-        }
-    }
-
-    /// Apply a faint sequence.
-    pub fn faint(self) -> Self {
-        Self {
-            buffer: format!("\x1B[2m{}", self.buffer),
-        }
-    }
-
-    /// Apply a italic sequence.
-    pub fn italic(self) -> Self {
-        Self {
-            buffer: format!("\x1B[3m{}", self.buffer),
-        }
-    }
-
-    /// Apply an underline sequence.
-    pub fn underline(self) -> Self {
-        Self {
-            buffer: format!("\x1B[4m{}", self.buffer),
-        }
-    }
-
-    /// Apply an inverse sequence.
-    pub fn inverse(self) -> Self {
-        Self {
-            buffer: format!("\x1B[7m{}", self.buffer),
-        }
-    }
-
-    /// Apply a strikethrough sequence.
-    pub fn strikethrough(self) -> Self {
-        Self {
-            buffer: format!("\x1B[9m{}", self.buffer),
-        }
-    }
-
-    /// Apply a fill color.
-    pub fn foreground(self, color: u8) -> Self {
-        Self {
-            buffer: format!("\x1B[38;5;{}m{}", color, self.buffer),
-        }
-    }
-
-    /// Apply a background color.
-    pub fn background(self, color: u8) -> Self {
-        Self {
-            buffer: format!("\x1B[48;5;{}m{}", color, self.buffer),
-        }
-    }
-
-    /// Return the styled string.
-    ///
-    /// This action will consume the builder.
-    pub fn to_string(self) -> String {
-        self.buffer
-    }
-}
+/// `White`, one of the 16 standard colors.
+///
+/// More colors are available as `Color::Indexed` or `Color::Rgb`.
+pub const WHITE: Color = Color::Ansi(15);
 
 /// Return text with a fill color.
-pub fn foreground<T>(text: T, color: u8) -> String
+pub fn foreground<T, C>(text: T, color: C) -> String
 where
     T: AsRef<str>,
+    C: Into<Color>,
 {
-    format!("\x1B[38;5;{}m{}\x1B[0m", color, text.as_ref())
+    Style::new().foreground(color).render(text)
 }
 
 /// Return text with a background color.
-pub fn background<T>(text: T, color: u8) -> String
+pub fn background<T, C>(text: T, color: C) -> String
 where
     T: AsRef<str>,
+    C: Into<Color>,
 {
-    format!("\x1B[48;5;{}m{}\x1B[0m", color, text.as_ref())
+    Style::new().background(color).render(text)
 }
 
 /// Return text with fill and background colors.
-pub fn fill_background<T>(text: T, fill_color: u8, background_color: u8) -> String
+pub fn fill_background<T, F, B>(text: T, fill_color: F, background_color: B) -> String
 where
     T: AsRef<str>,
+    F: Into<Color>,
+    B: Into<Color>,
 {
-    format!(
-        "\x1B[38;5;{}m\x1B[48;5;{}m{}\x1B[0m",
-        fill_color,
-        background_color,
-        text.as_ref()
-    )
+    Style::new()
+        .foreground(fill_color)
+        .background(background_color)
+        .render(text)
 }
 
 /// Return text with a bold sequence.
 #[macro_export]
 macro_rules! bold {
     ($text:expr) => {
-        format!("\x1B[1m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .bold()
+            .render(format!("{}", $text))
     };
 }
 
@@ -243,7 +136,9 @@ macro_rules! bold {
 #[macro_export]
 macro_rules! faint {
     ($text:expr) => {
-        format!("\x1B[2m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .faint()
+            .render(format!("{}", $text))
     };
 }
 
@@ -251,7 +146,9 @@ macro_rules! faint {
 #[macro_export]
 macro_rules! italic {
     ($text:expr) => {
-        format!("\x1B[3m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .italic()
+            .render(format!("{}", $text))
     };
 }
 
@@ -259,7 +156,9 @@ macro_rules! italic {
 #[macro_export]
 macro_rules! underline {
     ($text:expr) => {
-        format!("\x1B[4m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .underline()
+            .render(format!("{}", $text))
     };
 }
 
@@ -267,7 +166,9 @@ macro_rules! underline {
 #[macro_export]
 macro_rules! inverse {
     ($text:expr) => {
-        format!("\x1B[7m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .inverse()
+            .render(format!("{}", $text))
     };
 }
 
@@ -275,6 +176,19 @@ macro_rules! inverse {
 #[macro_export]
 macro_rules! strikethrough {
     ($text:expr) => {
-        format!("\x1B[9m{}\x1B[0m", $text)
+        $crate::font::Style::new()
+            .strikethrough()
+            .render(format!("{}", $text))
     };
 }
+
+/// Return a function that looks up environment variables in `vars`, in
+/// place of `std::env::var`.
+#[cfg(test)]
+fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    move |key| {
+        vars.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    }
+}
@@ -0,0 +1,168 @@
+use std::cell::Cell;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::backend::Backend;
+use crate::font::Color;
+
+thread_local! {
+    /// Whether the terminal of the program running on this thread has a
+    /// dark background, while a program is running.
+    static BACKGROUND: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// How long to wait for the terminal to report its background color.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Return true if the terminal has a dark background.
+///
+/// This chooses which color of a `Color::Adaptive` is drawn. While a
+/// `Program` is running, this is the background of its terminal, as set
+/// with `Program::with_dark_background` or detected when it started.
+/// Otherwise the background is described by `LOCKET_BACKGROUND` (`light` or
+/// `dark`) or `COLORFGBG`, and is assumed to be dark.
+pub fn has_dark_background() -> bool {
+    static DEFAULT: OnceLock<bool> = OnceLock::new();
+
+    BACKGROUND.get().unwrap_or_else(|| {
+        *DEFAULT.get_or_init(|| detect_from_env(|key| std::env::var(key).ok()).unwrap_or(true))
+    })
+}
+
+/// Sets the background of the terminal for the current thread, while a
+/// program runs on it, and restores the previous background when dropped.
+pub(crate) struct BackgroundScope {
+    /// The background before the scope was entered.
+    previous: Option<bool>,
+}
+
+impl BackgroundScope {
+    /// Set whether the terminal has a dark background until the scope is dropped.
+    pub(crate) fn enter(dark: bool) -> Self {
+        Self {
+            previous: BACKGROUND.replace(Some(dark)),
+        }
+    }
+}
+
+impl Drop for BackgroundScope {
+    fn drop(&mut self) {
+        BACKGROUND.set(self.previous);
+    }
+}
+
+/// Detect whether the terminal of a program that is starting has a dark
+/// background, or `None` if it cannot be told.
+///
+/// `LOCKET_BACKGROUND` takes precedence over asking the terminal, which
+/// takes precedence over `COLORFGBG`. Raw mode must be enabled, so that the
+/// answer of the terminal is not echoed.
+pub(crate) fn detect_background(backend: &mut dyn Backend) -> Option<bool> {
+    let var = |key: &str| std::env::var(key).ok();
+    if let Some(dark) = var("LOCKET_BACKGROUND").and_then(|value| parse_override(&value)) {
+        return Some(dark);
+    }
+
+    match backend.query_background(QUERY_TIMEOUT) {
+        Ok(Some(color)) => Some(is_dark(color)),
+        _ => detect_from_env(var),
+    }
+}
+
+/// Return true if the color is dark, judged by its perceived brightness.
+pub(crate) fn is_dark(color: Color) -> bool {
+    let (r, g, b) = color.rgb();
+    let brightness = 299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b);
+    brightness < 128 * 1000
+}
+
+/// Return whether the environment describes a dark background, reading
+/// environment variables with `var`.
+fn detect_from_env<F>(var: F) -> Option<bool>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if let Some(dark) = var("LOCKET_BACKGROUND").and_then(|value| parse_override(&value)) {
+        return Some(dark);
+    }
+
+    // `COLORFGBG` is set by some terminals to `foreground;background`, where
+    // each is one of the 16 standard colors.
+    let colorfgbg = var("COLORFGBG")?;
+    let background: u8 = colorfgbg.rsplit(';').next()?.parse().ok()?;
+    Some(matches!(background, 0..=6 | 8))
+}
+
+/// Parse the value of `LOCKET_BACKGROUND`.
+fn parse_override(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "dark" => Some(true),
+        "light" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse the answer of a terminal to an `OSC 11` query, such as
+/// `ESC ] 11 ; rgb:ffff/ffff/dddd BEL`, anywhere in the bytes.
+pub(crate) fn parse_background(bytes: &[u8]) -> Option<Color> {
+    let text = String::from_utf8_lossy(bytes);
+    let start = text.find("\x1B]11;")? + 5;
+    let rest = &text[start..];
+    let end = rest.find(['\x07', '\x1B'])?;
+
+    let mut components = rest[..end].strip_prefix("rgb:")?.split('/').map(|c| {
+        // Each component has from 1 to 4 hex digits, scale it to 8 bits.
+        let value = u32::from_str_radix(c, 16).ok()?;
+        let max = 16u32.checked_pow(u32::try_from(c.len()).ok()?)? - 1;
+        (1..=4)
+            .contains(&c.len())
+            .then(|| (value * 255 / max) as u8)
+    });
+
+    let color = Color::Rgb(
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    components.next().is_none().then_some(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::background::{detect_from_env, is_dark, parse_background};
+    use crate::font::{env, Color};
+
+    fn detect(vars: &[(&str, &str)]) -> Option<bool> {
+        detect_from_env(env(vars))
+    }
+
+    #[test]
+    fn test_parse_background() {
+        assert_eq!(
+            parse_background(b"\x1B]11;rgb:ffff/ffff/dddd\x07"),
+            Some(Color::Rgb(255, 255, 221))
+        );
+        // Terminated by `ST`, and followed by the answer to another query.
+        assert_eq!(
+            parse_background(b"\x1B]11;rgb:1e/1e/2e\x1B\\\x1B[?62c"),
+            Some(Color::Rgb(30, 30, 46))
+        );
+
+        assert_eq!(parse_background(b"\x1B]11;rgb:ff/ff"), None);
+        assert_eq!(parse_background(b"\x1B[?62c"), None);
+    }
+
+    #[test]
+    fn test_detect_from_env() {
+        assert_eq!(detect(&[("COLORFGBG", "15;0")]), Some(true));
+        assert_eq!(detect(&[("COLORFGBG", "0;default;15")]), Some(false));
+        assert_eq!(
+            detect(&[("COLORFGBG", "15;0"), ("LOCKET_BACKGROUND", "light")]),
+            Some(false)
+        );
+        assert_eq!(detect(&[]), None);
+
+        assert!(is_dark(Color::Rgb(30, 30, 46)));
+        assert!(!is_dark(Color::Rgb(253, 246, 227)));
+    }
+}
@@ -0,0 +1,76 @@
+/// Describes the characters that a border is drawn with.
+///
+/// Each character should occupy a single column. Borders other than the
+/// ones provided are created by setting each character.
+///
+/// # Examples
+///
+/// ```
+/// use locket::font::{Border, Style};
+///
+/// let stars = Border {
+///     top: '*',
+///     bottom: '*',
+///     left: '*',
+///     right: '*',
+///     top_left: '*',
+///     top_right: '*',
+///     bottom_left: '*',
+///     bottom_right: '*',
+/// };
+///
+/// let block = Style::new().border(stars).render("hi");
+/// assert_eq!(block, "****\n*hi*\n****");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Border {
+    pub top: char,
+    pub bottom: char,
+    pub left: char,
+    pub right: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+}
+
+impl Border {
+    /// A border of thin lines with square corners.
+    pub const NORMAL: Border = Border::new(['─', '─', '│', '│', '┌', '┐', '└', '┘']);
+
+    /// A border of thin lines with rounded corners.
+    pub const ROUNDED: Border = Border::new(['─', '─', '│', '│', '╭', '╮', '╰', '╯']);
+
+    /// A border of thick lines.
+    pub const THICK: Border = Border::new(['━', '━', '┃', '┃', '┏', '┓', '┗', '┛']);
+
+    /// A border of double lines.
+    pub const DOUBLE: Border = Border::new(['═', '═', '║', '║', '╔', '╗', '╚', '╝']);
+
+    /// A border of ASCII characters, for terminals without box drawing
+    /// characters.
+    pub const ASCII: Border = Border::new(['-', '-', '|', '|', '+', '+', '+', '+']);
+
+    /// Return a border from the top, bottom, left, right, top left, top
+    /// right, bottom left and bottom right characters.
+    const fn new(chars: [char; 8]) -> Self {
+        let [top, bottom, left, right, top_left, top_right, bottom_left, bottom_right] = chars;
+
+        Self {
+            top,
+            bottom,
+            left,
+            right,
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Border::NORMAL
+    }
+}
@@ -0,0 +1,405 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::font::{has_dark_background, Profile};
+
+/// Describes a color that text or its background may be drawn with.
+///
+/// Any `u8` converts to an XTERM 256 color, and any `(u8, u8, u8)` to a
+/// 24-bit color. Colors may also be parsed from a `#rrggbb` or `#rgb` hex
+/// code, or the name of one of the 16 standard colors.
+///
+/// An adaptive color is drawn as one of two colors, depending on whether
+/// the terminal has a light or dark background.
+///
+/// # Examples
+///
+/// ```
+/// use locket::font::Color;
+///
+/// assert_eq!("#ff8000".parse(), Ok(Color::Rgb(255, 128, 0)));
+/// assert_eq!("maroon".parse(), Ok(Color::Ansi(1)));
+/// assert_eq!(Color::from(208), Color::Indexed(208));
+/// ```
+///
+/// Use a color that is readable on any background:
+///
+/// ```
+/// use locket::font::{Style, Color, NAVY, YELLOW};
+///
+/// let text = Style::new()
+///     .foreground(Color::adaptive(NAVY, YELLOW))
+///     .render("Hello, World!");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the 16 standard colors, from 0 to 15.
+    ///
+    /// Terminals usually let the user choose how these colors look.
+    Ansi(u8),
+    /// One of the XTERM 256 colors.
+    ///
+    /// https://en.wikipedia.org/wiki/Xterm
+    Indexed(u8),
+    /// A 24-bit color, from red, green and blue components.
+    Rgb(u8, u8, u8),
+    /// A color that depends on the background of the terminal, created
+    /// with `Color::adaptive`.
+    Adaptive(AdaptiveColor),
+}
+
+/// A pair of colors, one drawn on light backgrounds and the other on dark
+/// backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdaptiveColor {
+    light: Base,
+    dark: Base,
+}
+
+impl AdaptiveColor {
+    /// Return the color drawn on light backgrounds.
+    pub fn light(&self) -> Color {
+        self.light.color()
+    }
+
+    /// Return the color drawn on dark backgrounds.
+    pub fn dark(&self) -> Color {
+        self.dark.color()
+    }
+}
+
+/// A color that is not adaptive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Base {
+    Ansi(u8),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Base {
+    const fn color(self) -> Color {
+        match self {
+            Base::Ansi(n) => Color::Ansi(n),
+            Base::Indexed(n) => Color::Indexed(n),
+            Base::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        }
+    }
+
+    /// Return the usual red, green and blue components of the color.
+    fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Base::Ansi(n) => PALETTE[usize::from(n & 15)],
+            Base::Indexed(n @ 0..=15) => PALETTE[usize::from(n)],
+            Base::Indexed(n @ 16..=231) => {
+                let n = usize::from(n - 16);
+                (CUBE[n / 36], CUBE[n / 6 % 6], CUBE[n % 6])
+            }
+            Base::Indexed(n) => {
+                let level = 8 + (n - 232) * 10;
+                (level, level, level)
+            }
+            Base::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+}
+
+/// Names of the 16 standard colors, in order.
+const NAMES: [&str; 16] = [
+    "black", "maroon", "green", "olive", "navy", "purple", "teal", "silver", "gray", "red", "lime",
+    "yellow", "blue", "fuchsia", "aqua", "white",
+];
+
+/// The usual appearance of the 16 standard colors, used to find the
+/// nearest standard color to any other.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each component in the 6x6x6 cube of the XTERM 256 colors.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Return a color drawn as `light` on light backgrounds, and as `dark`
+    /// on dark backgrounds.
+    ///
+    /// If either color is itself adaptive, its color for the same
+    /// background is used.
+    pub const fn adaptive(light: Color, dark: Color) -> Color {
+        let light = match light {
+            Color::Ansi(n) => Base::Ansi(n),
+            Color::Indexed(n) => Base::Indexed(n),
+            Color::Rgb(r, g, b) => Base::Rgb(r, g, b),
+            Color::Adaptive(color) => color.light,
+        };
+        let dark = match dark {
+            Color::Ansi(n) => Base::Ansi(n),
+            Color::Indexed(n) => Base::Indexed(n),
+            Color::Rgb(r, g, b) => Base::Rgb(r, g, b),
+            Color::Adaptive(color) => color.dark,
+        };
+
+        Color::Adaptive(AdaptiveColor { light, dark })
+    }
+
+    /// Return the color that is drawn on the background of the terminal,
+    /// which is the color itself unless it is adaptive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use locket::font::{has_dark_background, Color, NAVY, YELLOW};
+    ///
+    /// let color = Color::adaptive(NAVY, YELLOW).resolve();
+    /// assert_eq!(color, if has_dark_background() { YELLOW } else { NAVY });
+    /// assert_eq!(NAVY.resolve(), NAVY);
+    /// ```
+    pub fn resolve(self) -> Color {
+        self.base().color()
+    }
+
+    /// Return the nearest color that can be displayed with the profile,
+    /// or `None` if the profile has no colors.
+    ///
+    /// Adaptive colors are resolved first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use locket::font::{Color, Profile};
+    ///
+    /// let orange = Color::Rgb(255, 135, 0);
+    /// assert_eq!(orange.downsample(Profile::Ansi256), Some(Color::Indexed(208)));
+    /// assert_eq!(orange.downsample(Profile::NoColor), None);
+    /// ```
+    pub fn downsample(self, profile: Profile) -> Option<Color> {
+        let base = self.base();
+        let base = match (profile, base) {
+            (Profile::NoColor, _) => return None,
+            (Profile::TrueColor, _) | (_, Base::Ansi(_)) => base,
+            (_, Base::Indexed(n)) if n < 16 => Base::Ansi(n),
+            (Profile::Ansi256, Base::Indexed(_)) => base,
+            (Profile::Ansi256, Base::Rgb(r, g, b)) => Base::Indexed(nearest_indexed(r, g, b)),
+            (Profile::Ansi, _) => Base::Ansi(nearest(&PALETTE, base.rgb()) as u8),
+        };
+
+        Some(base.color())
+    }
+
+    /// Return the usual red, green and blue components of the color.
+    pub(crate) fn rgb(self) -> (u8, u8, u8) {
+        self.base().rgb()
+    }
+
+    /// Return the parameters of a Select Graphic Rendition sequence that
+    /// draw text with the color.
+    pub(crate) fn foreground_code(&self) -> String {
+        match self.base() {
+            Base::Ansi(n @ 0..=7) => (30 + n).to_string(),
+            Base::Ansi(n) => (90 + (n & 7)).to_string(),
+            Base::Indexed(n) => format!("38;5;{}", n),
+            Base::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+
+    /// Return the parameters of a Select Graphic Rendition sequence that
+    /// draw the background with the color.
+    pub(crate) fn background_code(&self) -> String {
+        match self.base() {
+            Base::Ansi(n @ 0..=7) => (40 + n).to_string(),
+            Base::Ansi(n) => (100 + (n & 7)).to_string(),
+            Base::Indexed(n) => format!("48;5;{}", n),
+            Base::Rgb(r, g, b) => format!("48;2;{};{};{}", r, g, b),
+        }
+    }
+
+    /// Return the color drawn on the background of the terminal.
+    fn base(self) -> Base {
+        match self {
+            Color::Ansi(n) => Base::Ansi(n),
+            Color::Indexed(n) => Base::Indexed(n),
+            Color::Rgb(r, g, b) => Base::Rgb(r, g, b),
+            Color::Adaptive(color) if has_dark_background() => color.dark,
+            Color::Adaptive(color) => color.light,
+        }
+    }
+}
+
+/// Return the index of the XTERM 256 color nearest to the components,
+/// from either the color cube or the gray ramp.
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE.len())
+            .min_by_key(|i| CUBE[*i].abs_diff(c))
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE[ri], CUBE[gi], CUBE[bi]);
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray = (average.saturating_sub(3) / 10).min(23) as u8;
+    let level = 8 + gray * 10;
+
+    if distance((level, level, level), (r, g, b)) < distance(cube, (r, g, b)) {
+        232 + gray
+    } else {
+        16 + (36 * ri + 6 * gi + bi) as u8
+    }
+}
+
+/// Return the index of the color in the palette nearest to the target.
+fn nearest(palette: &[(u8, u8, u8)], target: (u8, u8, u8)) -> usize {
+    (0..palette.len())
+        .min_by_key(|i| distance(palette[*i], target))
+        .unwrap_or(0)
+}
+
+/// Return the squared distance between two colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+impl From<u8> for Color {
+    fn from(value: u8) -> Self {
+        Color::Indexed(value)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color::Rgb(r, g, b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseColorError(s.to_string());
+
+        let Some(hex) = s.strip_prefix('#') else {
+            let name = s.to_ascii_lowercase();
+            let name = match name.as_str() {
+                "grey" => "gray",
+                "magenta" => "fuchsia",
+                "cyan" => "aqua",
+                name => name,
+            };
+
+            return NAMES
+                .iter()
+                .position(|n| *n == name)
+                .map(|n| Color::Ansi(n as u8))
+                .ok_or_else(error);
+        };
+
+        if !hex.is_ascii() {
+            return Err(error());
+        }
+        let component = |s: &str| u8::from_str_radix(s, 16).map_err(|_| error());
+
+        match hex.len() {
+            6 => Ok(Color::Rgb(
+                component(&hex[0..2])?,
+                component(&hex[2..4])?,
+                component(&hex[4..6])?,
+            )),
+            // Each digit is repeated, `#f80` is `#ff8800`.
+            3 => Ok(Color::Rgb(
+                component(&hex[0..1])? * 17,
+                component(&hex[1..2])? * 17,
+                component(&hex[2..3])? * 17,
+            )),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// An error returned when a string does not describe a `Color`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid color: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::{BackgroundScope, Color, Profile};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("#1E90ff".parse(), Ok(Color::Rgb(30, 144, 255)));
+        assert_eq!("#f80".parse(), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!("Cyan".parse(), Ok(Color::Ansi(14)));
+
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("#gggggg".parse::<Color>().is_err());
+        assert!("orange".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(Color::Ansi(1).foreground_code(), "31");
+        assert_eq!(Color::Ansi(9).background_code(), "101");
+        assert_eq!(Color::Indexed(208).foreground_code(), "38;5;208");
+        assert_eq!(Color::Rgb(1, 2, 3).background_code(), "48;2;1;2;3");
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let color = Color::adaptive(Color::Ansi(4), Color::Rgb(255, 255, 0));
+
+        // The background is set for the current thread only.
+        let light = BackgroundScope::enter(false);
+        assert_eq!(color.foreground_code(), "34");
+        let dark = BackgroundScope::enter(true);
+        assert_eq!(color.foreground_code(), "38;2;255;255;0");
+        assert_eq!(color.downsample(Profile::Ansi), Some(Color::Ansi(11)));
+
+        // The previous background is restored when a scope ends.
+        drop(dark);
+        assert_eq!(color.foreground_code(), "34");
+        drop(light);
+
+        // Nested adaptive colors take the color for the same background.
+        let nested = Color::adaptive(color, Color::Ansi(1));
+        assert_eq!(nested, Color::adaptive(Color::Ansi(4), Color::Ansi(1)));
+    }
+
+    #[test]
+    fn test_downsample() {
+        let gray = Color::Rgb(128, 128, 128);
+        assert_eq!(gray.downsample(Profile::Ansi256), Some(Color::Indexed(244)));
+        assert_eq!(gray.downsample(Profile::Ansi), Some(Color::Ansi(8)));
+
+        assert_eq!(
+            Color::Indexed(196).downsample(Profile::Ansi),
+            Some(Color::Ansi(9))
+        );
+        assert_eq!(
+            Color::Indexed(4).downsample(Profile::Ansi),
+            Some(Color::Ansi(4))
+        );
+        assert_eq!(Color::Ansi(4).downsample(Profile::NoColor), None);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::ansi::{self, Token};
+use crate::font::Color;
+
+/// Describes the colors that a terminal can display.
+///
+/// Profiles are ordered, each one supports every color of the profiles
+/// before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Profile {
+    /// No colors, only text attributes such as bold.
+    NoColor,
+    /// The 16 standard colors.
+    Ansi,
+    /// The XTERM 256 colors.
+    Ansi256,
+    /// Any 24-bit color.
+    TrueColor,
+}
+
+impl Profile {
+    /// Detect the profile of the terminal from the environment.
+    ///
+    /// `NO_COLOR` disables color, as does output that is not a terminal
+    /// unless `CLICOLOR_FORCE` is set. Otherwise `COLORTERM` and `TERM`
+    /// describe the colors the terminal supports.
+    pub fn detect(terminal: bool) -> Profile {
+        detect_from(|key| std::env::var(key).ok(), terminal)
+    }
+
+    /// Return the text with the colors of its escape sequences replaced by
+    /// the nearest colors that can be displayed with the profile.
+    ///
+    /// Frames drawn by a `Program` are downsampled to the profile of its
+    /// terminal. Styled text written by other means may be downsampled with
+    /// this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use locket::font::{Profile, Style};
+    ///
+    /// let text = Style::new().foreground((250, 10, 10)).render("error");
+    ///
+    /// // The 24-bit color is drawn as the nearest standard color.
+    /// assert_eq!(Profile::Ansi.downsample(&text), "\x1B[91merror\x1B[0m");
+    /// ```
+    pub fn downsample(self, text: &str) -> String {
+        if self == Profile::TrueColor {
+            return text.to_string();
+        }
+
+        let mut downsampled = String::with_capacity(text.len());
+        for token in ansi::tokens(text) {
+            match token {
+                Token::Escape(sequence) => {
+                    match sequence
+                        .strip_prefix("\x1B[")
+                        .and_then(|s| s.strip_suffix('m'))
+                    {
+                        Some(params) => downsampled.push_str(&self.downsample_sgr(params)),
+                        None => downsampled.push_str(sequence),
+                    }
+                }
+                Token::Text(grapheme) => downsampled.push_str(grapheme),
+            }
+        }
+
+        downsampled
+    }
+
+    /// Return the Select Graphic Rendition sequence with the parameters,
+    /// with its colors downsampled.
+    ///
+    /// A sequence that is left without parameters is removed, rather than
+    /// becoming a reset.
+    fn downsample_sgr(self, params: &str) -> String {
+        if params.is_empty() {
+            return "\x1B[m".to_string();
+        }
+
+        let number = |param: Option<&str>| param.and_then(|p| p.parse::<u8>().ok());
+
+        let mut codes = Vec::new();
+        let mut params = params.split(';');
+        while let Some(param) = params.next() {
+            let (foreground, color) = match param.parse::<u8>() {
+                Ok(n @ 30..=37) => (true, Some(Color::Ansi(n - 30))),
+                Ok(n @ 90..=97) => (true, Some(Color::Ansi(n - 82))),
+                Ok(n @ 40..=47) => (false, Some(Color::Ansi(n - 40))),
+                Ok(n @ 100..=107) => (false, Some(Color::Ansi(n - 92))),
+                Ok(n @ (38 | 48)) => {
+                    let color = match params.next() {
+                        Some("5") => number(params.next()).map(Color::Indexed),
+                        Some("2") => {
+                            match (
+                                number(params.next()),
+                                number(params.next()),
+                                number(params.next()),
+                            ) {
+                                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                    (n == 38, color)
+                }
+                _ => {
+                    codes.push(param.to_string());
+                    continue;
+                }
+            };
+
+            match color.and_then(|c| c.downsample(self)) {
+                Some(color) if foreground => codes.push(color.foreground_code()),
+                Some(color) => codes.push(color.background_code()),
+                None => {}
+            }
+        }
+
+        if codes.is_empty() {
+            return String::new();
+        }
+
+        format!("\x1B[{}m", codes.join(";"))
+    }
+}
+
+/// Detect the profile, reading environment variables with `var`.
+fn detect_from<F>(var: F, terminal: bool) -> Profile
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let set = |key| var(key).is_some_and(|value| !value.is_empty());
+
+    if set("NO_COLOR") {
+        return Profile::NoColor;
+    }
+    let forced = var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+    if !terminal && !forced {
+        return Profile::NoColor;
+    }
+
+    let colorterm = var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return Profile::TrueColor;
+    }
+
+    let term = var("TERM").unwrap_or_default().to_ascii_lowercase();
+    if term.is_empty() || term == "dumb" {
+        // Forced color with no description of the terminal gets the
+        // colors that every terminal supports.
+        return if forced {
+            Profile::Ansi
+        } else {
+            Profile::NoColor
+        };
+    }
+    if term.contains("truecolor") || term.contains("direct") {
+        return Profile::TrueColor;
+    }
+    if term.contains("256color") {
+        return Profile::Ansi256;
+    }
+
+    Profile::Ansi
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::env;
+    use crate::font::profile::{detect_from, Profile};
+
+    fn detect(vars: &[(&str, &str)], terminal: bool) -> Profile {
+        detect_from(env(vars), terminal)
+    }
+
+    #[test]
+    fn test_detect() {
+        let term = ("TERM", "xterm-256color");
+
+        assert_eq!(detect(&[term], true), Profile::Ansi256);
+        assert_eq!(
+            detect(&[term, ("COLORTERM", "truecolor")], true),
+            Profile::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "xterm")], true), Profile::Ansi);
+        assert_eq!(detect(&[("TERM", "dumb")], true), Profile::NoColor);
+
+        // Color is disabled by `NO_COLOR`, or output that is not a terminal.
+        assert_eq!(detect(&[term, ("NO_COLOR", "1")], true), Profile::NoColor);
+        assert_eq!(detect(&[term], false), Profile::NoColor);
+        assert_eq!(
+            detect(&[term, ("CLICOLOR_FORCE", "1")], false),
+            Profile::Ansi256
+        );
+    }
+
+    #[test]
+    fn test_downsample() {
+        let text = "\x1B[1;38;2;255;135;0ma\x1B[48;5;9;4mb\x1B[0m\x1B[m";
+
+        assert_eq!(Profile::TrueColor.downsample(text), text);
+        assert_eq!(
+            Profile::Ansi256.downsample(text),
+            "\x1B[1;38;5;208ma\x1B[101;4mb\x1B[0m\x1B[m"
+        );
+
+        // Sequences left without parameters are removed, not made resets.
+        assert_eq!(
+            Profile::NoColor.downsample("\x1B[31mred\x1B[0m \x1B[1;31mbold"),
+            "red\x1B[0m \x1B[1mbold"
+        );
+    }
+}
@@ -0,0 +1,624 @@
+use crate::ansi;
+use crate::font::{Border, Color};
+use crate::layout;
+
+/// Describes how text is drawn.
+///
+/// Styles nest: text rendered with one style may be placed inside text
+/// rendered with another, and the outer style is restored after the inner
+/// text ends.
+///
+/// # Examples
+///
+/// Create a bold, underlined maroon greeting:
+///
+/// ```
+/// use locket::font::{Style, MAROON};
+///
+/// let greeting = Style::new()
+///     .bold()
+///     .underline()
+///     .foreground(MAROON)
+///     .render("Hello, World!");
+/// ```
+///
+/// Highlight a word within a sentence:
+///
+/// ```
+/// use locket::font::{Style, BLUE, YELLOW};
+///
+/// let word = Style::new().foreground(YELLOW).render("important");
+/// let sentence = Style::new()
+///     .foreground(BLUE)
+///     .render(format!("An {} sentence.", word));
+///
+/// // The blue foreground is restored after the highlighted word.
+/// assert_eq!(
+///     sentence,
+///     "\x1B[94mAn \x1B[93mimportant\x1B[0m\x1B[94m sentence.\x1B[0m"
+/// );
+/// ```
+///
+/// Draw a panel, with padding around centered text:
+///
+/// ```
+/// use locket::font::{Align, Style};
+///
+/// let panel = Style::new()
+///     .padding(1, 2, 1, 2)
+///     .width(12)
+///     .align(Align::Center)
+///     .render("Hello,\nWorld!");
+///
+/// assert_eq!(
+///     panel,
+///     "            \n   Hello,   \n   World!   \n            "
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Style {
+    /// The color of the text.
+    foreground: Option<Color>,
+    /// The color behind the text.
+    background: Option<Color>,
+    bold: bool,
+    faint: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+    strikethrough: bool,
+    /// Space between the text and the edges of the block, which is drawn
+    /// with the style.
+    padding: Sides,
+    /// Space around the block, which is not drawn with the style.
+    margin: Sides,
+    /// The width of the block, including padding.
+    width: Option<usize>,
+    /// The height of the block, including padding.
+    height: Option<usize>,
+    /// How each line is placed within the block.
+    align: Align,
+    /// The border drawn around the block, outside the padding.
+    border: Option<Border>,
+    /// Which sides of the border are drawn.
+    border_sides: Sides<bool>,
+    /// The color of the border.
+    border_foreground: Option<Color>,
+    /// The color behind the border.
+    border_background: Option<Color>,
+    /// Text drawn within the top border.
+    border_title: Option<String>,
+}
+
+/// Describes how lines of text are placed within a block that is wider
+/// than they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// A value for each side of a block, such as the size of padding, in
+/// columns for the left and right sides and in lines for the top and bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Sides<T = usize> {
+    top: T,
+    right: T,
+    bottom: T,
+    left: T,
+}
+
+impl<T> Sides<T> {
+    fn new(top: T, right: T, bottom: T, left: T) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+}
+
+impl Style {
+    /// Return a new instance of `Style`, which does not change how text is drawn.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply bold.
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Apply faint.
+    pub fn faint(self) -> Self {
+        Self {
+            faint: true,
+            ..self
+        }
+    }
+
+    /// Apply italic.
+    pub fn italic(self) -> Self {
+        Self {
+            italic: true,
+            ..self
+        }
+    }
+
+    /// Apply an underline.
+    pub fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// Swap the foreground and background colors.
+    pub fn inverse(self) -> Self {
+        Self {
+            inverse: true,
+            ..self
+        }
+    }
+
+    /// Apply a strikethrough.
+    pub fn strikethrough(self) -> Self {
+        Self {
+            strikethrough: true,
+            ..self
+        }
+    }
+
+    /// Apply a fill color.
+    pub fn foreground<C>(self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        Self {
+            foreground: Some(color.into()),
+            ..self
+        }
+    }
+
+    /// Apply a background color.
+    pub fn background<C>(self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        Self {
+            background: Some(color.into()),
+            ..self
+        }
+    }
+
+    /// Apply padding to the top, right, bottom and left of the block, in
+    /// that order.
+    ///
+    /// Padding is drawn with the style, so it is filled with the background
+    /// color.
+    pub fn padding(self, top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Self {
+            padding: Sides::new(top, right, bottom, left),
+            ..self
+        }
+    }
+
+    /// Apply a margin to the top, right, bottom and left of the block, in
+    /// that order.
+    ///
+    /// Margins are drawn as plain spaces.
+    pub fn margin(self, top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Self {
+            margin: Sides::new(top, right, bottom, left),
+            ..self
+        }
+    }
+
+    /// Set the width of the block in columns, including padding.
+    ///
+    /// Shorter lines are filled with spaces, longer lines are wrapped
+    /// between words.
+    pub fn width(self, width: usize) -> Self {
+        Self {
+            width: Some(width),
+            ..self
+        }
+    }
+
+    /// Set the height of the block in lines, including padding.
+    ///
+    /// Blank lines are added below shorter text, longer text is kept whole.
+    pub fn height(self, height: usize) -> Self {
+        Self {
+            height: Some(height),
+            ..self
+        }
+    }
+
+    /// Set how lines are placed within the block.
+    pub fn align(self, align: Align) -> Self {
+        Self { align, ..self }
+    }
+
+    /// Draw a border around the block, on every side.
+    pub fn border(self, border: Border) -> Self {
+        Self {
+            border: Some(border),
+            border_sides: Sides::new(true, true, true, true),
+            ..self
+        }
+    }
+
+    /// Set which sides of the border are drawn, the top, right, bottom and
+    /// left in that order.
+    ///
+    /// The border is `Border::NORMAL` unless another was set with `border`.
+    pub fn border_sides(self, top: bool, right: bool, bottom: bool, left: bool) -> Self {
+        Self {
+            border: Some(self.border.unwrap_or_default()),
+            border_sides: Sides::new(top, right, bottom, left),
+            ..self
+        }
+    }
+
+    /// Apply a color to the border.
+    pub fn border_foreground<C>(self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        Self {
+            border_foreground: Some(color.into()),
+            ..self
+        }
+    }
+
+    /// Apply a color behind the border.
+    pub fn border_background<C>(self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        Self {
+            border_background: Some(color.into()),
+            ..self
+        }
+    }
+
+    /// Draw a title within the top border, near its left end.
+    ///
+    /// A title that does not fit is truncated. Nothing is drawn unless the
+    /// top of the border is drawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use locket::font::{Border, Style};
+    ///
+    /// let panel = Style::new()
+    ///     .border(Border::ROUNDED)
+    ///     .border_title("Log")
+    ///     .width(8)
+    ///     .render("ok");
+    ///
+    /// assert_eq!(panel, "╭─Log────╮\n│ok      │\n╰────────╯");
+    /// ```
+    pub fn border_title<T>(self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            border_title: Some(title.into()),
+            ..self
+        }
+    }
+
+    /// Return the text drawn with the style.
+    ///
+    /// Wherever styles are reset within the text, such as at the end of
+    /// nested styled text, this style is applied again. Colors are drawn
+    /// as given, and downsampled to the profile of the terminal when the
+    /// frame is written.
+    ///
+    /// If the style has padding, margins, a size or an alignment, the text
+    /// is drawn as a block, where every line has the same width. Widths are
+    /// measured in columns, ignoring escape sequences.
+    pub fn render<T>(&self, text: T) -> String
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        if self.is_block() {
+            self.render_block(text)
+        } else {
+            self.render_inline(text)
+        }
+    }
+
+    /// Return true if the text is drawn as a block.
+    fn is_block(&self) -> bool {
+        self.padding != Sides::default()
+            || self.margin != Sides::default()
+            || self.width.is_some()
+            || self.height.is_some()
+            || self.align != Align::Left
+            || self.border.is_some()
+    }
+
+    /// Return the lines of text drawn as a block, each drawn with the style.
+    fn render_block(&self, text: &str) -> String {
+        let padding = self.padding;
+        let margin = self.margin;
+
+        let inner = self.width.map_or(ansi::width(text), |width| {
+            width.saturating_sub(padding.left + padding.right)
+        });
+        let text = match self.width {
+            Some(_) if ansi::width(text) > inner => ansi::wrap(text, inner),
+            _ => text.to_string(),
+        };
+
+        let mut lines: Vec<&str> = text.lines().collect();
+        if lines.is_empty() {
+            lines.push("");
+        }
+        let height = self.height.map_or(0, |height| {
+            height.saturating_sub(padding.top + padding.bottom)
+        });
+        while lines.len() < height {
+            lines.push("");
+        }
+
+        let outer = padding.left + inner + padding.right;
+        let spaces = |n: usize| " ".repeat(n);
+        let blank = self.render_inline(spaces(outer));
+
+        let mut block = Vec::with_capacity(lines.len() + padding.top + padding.bottom);
+        block.extend(std::iter::repeat_n(blank.clone(), padding.top));
+        for line in lines {
+            block.push(self.render_inline(format!(
+                "{}{}{}",
+                spaces(padding.left),
+                layout::align_line(line, inner, self.align),
+                spaces(padding.right)
+            )));
+        }
+        block.extend(std::iter::repeat_n(blank, padding.bottom));
+
+        let (block, outer) = self.add_border(block, outer);
+
+        let full = margin.left + outer + margin.right;
+        let mut rendered = Vec::with_capacity(block.len() + margin.top + margin.bottom);
+        rendered.extend(std::iter::repeat_n(spaces(full), margin.top));
+        for line in block {
+            rendered.push(format!(
+                "{}{}{}",
+                spaces(margin.left),
+                line,
+                spaces(margin.right)
+            ));
+        }
+        rendered.extend(std::iter::repeat_n(spaces(full), margin.bottom));
+
+        rendered.join("\n")
+    }
+
+    /// Return the lines of a block that is `width` columns wide with the
+    /// border drawn around them, and the width of the bordered block.
+    fn add_border(&self, block: Vec<String>, width: usize) -> (Vec<String>, usize) {
+        let Some(border) = self.border else {
+            return (block, width);
+        };
+        let sides = self.border_sides;
+        let style = Style {
+            foreground: self.border_foreground,
+            background: self.border_background,
+            ..Style::default()
+        };
+
+        // Each horizontal edge includes the corners of the sides that are drawn.
+        let edge = |left: char, fill: char, right: char, title: Option<&str>| {
+            let mut line = String::new();
+            if sides.left {
+                line.push(left);
+            }
+
+            let mut filled = 0;
+            if let Some(title) = title.filter(|_| width > 1) {
+                line.push(fill);
+                let title = ansi::truncate(title, width - 1, "");
+                filled = 1 + ansi::width(&title);
+                line.push_str(&title);
+            }
+            line.extend(std::iter::repeat_n(fill, width - filled));
+
+            if sides.right {
+                line.push(right);
+            }
+            style.render_inline(line)
+        };
+
+        let mut bordered = Vec::with_capacity(block.len() + 2);
+        if sides.top {
+            bordered.push(edge(
+                border.top_left,
+                border.top,
+                border.top_right,
+                self.border_title.as_deref(),
+            ));
+        }
+        let left = style.render_inline(border.left.to_string());
+        let right = style.render_inline(border.right.to_string());
+        for line in block {
+            bordered.push(format!(
+                "{}{}{}",
+                if sides.left { left.as_str() } else { "" },
+                line,
+                if sides.right { right.as_str() } else { "" },
+            ));
+        }
+        if sides.bottom {
+            bordered.push(edge(
+                border.bottom_left,
+                border.bottom,
+                border.bottom_right,
+                None,
+            ));
+        }
+
+        let width = width + usize::from(sides.left) + usize::from(sides.right);
+        (bordered, width)
+    }
+
+    /// Return the text drawn with the style, without changing its layout.
+    fn render_inline<T>(&self, text: T) -> String
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        let sgr = self.sgr();
+        if sgr.is_empty() {
+            return text.to_string();
+        }
+
+        let mut rendered = String::with_capacity(text.len() + sgr.len() * 2 + 4);
+        rendered.push_str(&sgr);
+
+        let mut i = 0;
+        while i < text.len() {
+            let len = ansi::escape_len(&text[i..]);
+            if len == 0 {
+                let c = text[i..].chars().next().unwrap();
+                rendered.push(c);
+                i += c.len_utf8();
+                continue;
+            }
+
+            let sequence = &text[i..i + len];
+            rendered.push_str(sequence);
+            if ansi::is_reset(sequence) {
+                rendered.push_str(&sgr);
+            }
+            i += len;
+        }
+
+        rendered.push_str("\x1B[0m");
+        rendered
+    }
+
+    /// Return the Select Graphic Rendition sequence that applies the style,
+    /// which is empty for the default style.
+    pub(crate) fn sgr(&self) -> String {
+        let mut codes = Vec::new();
+
+        let modifiers = [
+            (self.bold, "1"),
+            (self.faint, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.inverse, "7"),
+            (self.strikethrough, "9"),
+        ];
+        for (enabled, code) in modifiers {
+            if enabled {
+                codes.push(code.to_string());
+            }
+        }
+
+        if let Some(color) = self.foreground {
+            codes.push(color.foreground_code());
+        }
+        if let Some(color) = self.background {
+            codes.push(color.background_code());
+        }
+
+        if codes.is_empty() {
+            return String::new();
+        }
+
+        format!("\x1B[{}m", codes.join(";"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::{Align, Border, Style};
+
+    #[test]
+    fn test_render_nested() {
+        let inner = Style::new().italic().render("b");
+        let middle = Style::new().foreground(1).render(format!("a{}c", inner));
+        let outer = Style::new().bold().render(format!("<{}>", middle));
+
+        // After each reset, the outer style is applied before the middle.
+        assert_eq!(
+            outer,
+            "\x1B[1m<\x1B[38;5;1ma\x1B[3mb\x1B[0m\x1B[1m\x1B[38;5;1mc\x1B[0m\x1B[1m>\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_render_block() {
+        // Padding is drawn with the style, margins are not.
+        let block = Style::new()
+            .inverse()
+            .padding(0, 1, 0, 1)
+            .margin(1, 0, 0, 2)
+            .render("ab\nc");
+        assert_eq!(block, "      \n  \x1B[7m ab \x1B[0m\n  \x1B[7m c  \x1B[0m");
+
+        // Escape sequences take no columns.
+        let word = Style::new().bold().render("日本");
+        let block = Style::new()
+            .width(6)
+            .height(2)
+            .align(Align::Right)
+            .render(&word);
+        assert_eq!(block, format!("  {}\n      ", word));
+    }
+
+    #[test]
+    fn test_render_width() {
+        // Shorter lines are filled to the width.
+        let block = Style::new().width(6).padding(0, 1, 0, 1).render("ab\nc");
+        assert_eq!(block, " ab   \n c    ");
+
+        // Longer lines are wrapped between words, rather than kept whole.
+        let block = Style::new()
+            .width(7)
+            .padding(0, 1, 0, 1)
+            .render("one two three");
+        assert_eq!(block, " one   \n two   \n three ");
+    }
+
+    #[test]
+    fn test_render_border() {
+        // Corners are only drawn where both sides are.
+        let block = Style::new()
+            .border_sides(false, true, true, false)
+            .render("a\nbc");
+        assert_eq!(block, "a │\nbc│\n──┘");
+
+        let block = Style::new()
+            .border(Border::ASCII)
+            .border_foreground(1)
+            .border_title("long title")
+            .render("abc");
+        assert_eq!(
+            block,
+            "\x1B[38;5;1m+-lo+\x1B[0m\n\x1B[38;5;1m|\x1B[0mabc\x1B[38;5;1m|\x1B[0m\n\x1B[38;5;1m+---+\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_render_default() {
+        assert_eq!(Style::new().render("plain"), "plain");
+
+        // A zero within a color is not a reset.
+        assert_eq!(
+            Style::new().bold().render("\x1B[38;5;0mx"),
+            "\x1B[1m\x1B[38;5;0mx\x1B[0m"
+        );
+    }
+}
@@ -1,22 +1,250 @@
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
 use crate::error::Error;
 
+/// Most pipes that may be notified of window size changes at the same time.
+const MAX_RESIZE_PIPES: usize = 16;
+
+/// Write ends of the pipes that `handle_signal` notifies of a window size
+/// change, or -1 for a free slot.
+///
+/// Atomics are used, rather than a lock, because the signal handler may
+/// interrupt a thread that holds it.
+static RESIZE_PIPES: [AtomicI32; MAX_RESIZE_PIPES] =
+    [const { AtomicI32::new(-1) }; MAX_RESIZE_PIPES];
+
+/// Number of signal handlers that are writing to the pipes.
+static HANDLING: AtomicUsize = AtomicUsize::new(0);
+
+/// The pipes that are registered, and the action for SIGWINCH before the
+/// first of them was.
+static RESIZE_ACTION: Mutex<(usize, Option<libc::sigaction>)> = Mutex::new((0, None));
+
 /// Return the (row, column) size of the terminal.
-pub unsafe fn get_ui_size() -> Result<(u16, u16), Error> {
+pub unsafe fn get_ui_size(fd: libc::c_int) -> Result<(u16, u16), Error> {
     // <sys/ioctl.h>
     let mut size: libc::winsize = std::mem::zeroed();
 
-    if libc::ioctl(
-        libc::STDOUT_FILENO,
-        libc::TIOCGWINSZ.into(),
-        &mut size as *mut _,
-    ) != 0
-    {
-        return Err(Error::FFI("unable to determine terminal size".into()));
+    if libc::ioctl(fd, libc::TIOCGWINSZ, &mut size as *mut _) != 0 {
+        return Err(Error::FFI("unable to determine terminal size"));
     }
 
     Ok((size.ws_row as u16, size.ws_col as u16))
 }
 
+/// Return the name of the host.
+pub unsafe fn get_hostname() -> Result<String, Error> {
+    // <unistd.h>
+    let mut name = [0u8; 256];
+    c_err(
+        libc::gethostname(name.as_mut_ptr() as *mut libc::c_char, name.len()),
+        "unable to determine hostname",
+    )?;
+
+    let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+    Ok(String::from_utf8_lossy(&name[..len]).into_owned())
+}
+
+/// Put the terminal in raw mode, returning the previous mode.
+pub unsafe fn enable_raw_mode(fd: libc::c_int) -> Result<libc::termios, Error> {
+    // <termios.h>
+    let mut mode: libc::termios = std::mem::zeroed();
+    c_err(
+        libc::tcgetattr(fd, &mut mode),
+        "unable to get terminal mode",
+    )?;
+
+    let mut raw = mode;
+    libc::cfmakeraw(&mut raw);
+    c_err(
+        libc::tcsetattr(fd, libc::TCSANOW, &raw),
+        "unable to set terminal mode",
+    )?;
+
+    Ok(mode)
+}
+
+/// Restore a terminal mode returned by `enable_raw_mode`.
+pub unsafe fn set_mode(fd: libc::c_int, mode: &libc::termios) -> Result<(), Error> {
+    c_err(
+        libc::tcsetattr(fd, libc::TCSANOW, mode),
+        "unable to set terminal mode",
+    )
+}
+
+/// Handle SIGWINCH, returning a pipe that receives a byte each time
+/// the window size changes.
+///
+/// The pipe is notified until the returned `ResizeWatch` is dropped, which
+/// closes its write end. The previous action for SIGWINCH is restored once
+/// every `ResizeWatch` has been dropped.
+pub unsafe fn watch_resize() -> Result<(std::os::fd::OwnedFd, ResizeWatch), Error> {
+    // <signal.h>
+    let mut fds = [0; 2];
+    c_err(libc::pipe(fds.as_mut_ptr()), "unable to create pipe")?;
+    let read: std::os::fd::OwnedFd = std::os::fd::FromRawFd::from_raw_fd(fds[0]);
+    let write: std::os::fd::OwnedFd = std::os::fd::FromRawFd::from_raw_fd(fds[1]);
+
+    // The signal handler must never block on a full pipe.
+    let flags = libc::fcntl(fds[1], libc::F_GETFL);
+    if flags < 0
+        || libc::fcntl(fds[1], libc::F_SETFL, flags | libc::O_NONBLOCK) < 0
+        || fds
+            .iter()
+            .any(|fd| libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) < 0)
+    {
+        return Err(Error::IO(std::io::Error::last_os_error()));
+    }
+
+    let mut action = RESIZE_ACTION.lock().unwrap();
+    if action.0 == 0 {
+        let mut handler: libc::sigaction = std::mem::zeroed();
+        handler.sa_sigaction = handle_signal as *const () as libc::sighandler_t;
+        handler.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+        libc::sigemptyset(&mut handler.sa_mask);
+
+        let mut previous: libc::sigaction = std::mem::zeroed();
+        c_err(
+            libc::sigaction(libc::SIGWINCH, &handler, &mut previous),
+            "unable to handle SIGWINCH",
+        )?;
+        action.1 = Some(previous);
+    }
+
+    let fd = std::os::fd::IntoRawFd::into_raw_fd(write);
+    let Some(slot) = RESIZE_PIPES.iter().position(|slot| {
+        slot.compare_exchange(-1, fd, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }) else {
+        libc::close(fd);
+        if action.0 == 0 {
+            restore_action(&mut action);
+        }
+        return Err(Error::FFI("too many pipes watching SIGWINCH"));
+    };
+    action.0 += 1;
+
+    Ok((read, ResizeWatch { slot }))
+}
+
+/// Registration of a pipe with `watch_resize`, which is removed when dropped.
+#[derive(Debug)]
+pub struct ResizeWatch {
+    /// Index of the write end of the pipe in `RESIZE_PIPES`.
+    slot: usize,
+}
+
+impl Drop for ResizeWatch {
+    fn drop(&mut self) {
+        let mut action = RESIZE_ACTION.lock().unwrap();
+        let fd = RESIZE_PIPES[self.slot].swap(-1, Ordering::SeqCst);
+
+        // A handler that loaded the descriptor before it was removed may
+        // still be writing to it.
+        while HANDLING.load(Ordering::SeqCst) > 0 {
+            std::thread::yield_now();
+        }
+        unsafe { libc::close(fd) };
+
+        action.0 -= 1;
+        if action.0 == 0 {
+            restore_action(&mut action);
+        }
+    }
+}
+
+/// Restore the action for SIGWINCH saved by `watch_resize`.
+fn restore_action(action: &mut (usize, Option<libc::sigaction>)) {
+    if let Some(previous) = action.1.take() {
+        unsafe { libc::sigaction(libc::SIGWINCH, &previous, std::ptr::null_mut()) };
+    }
+}
+
+/// Wait until any of the descriptors can be read from, or the timeout
+/// in milliseconds elapses.
+///
+/// Returns whether each descriptor can be read from.
+pub unsafe fn poll_read(fds: &[libc::c_int], timeout: libc::c_int) -> Result<Vec<bool>, Error> {
+    // <poll.h>
+    let mut pollfds: Vec<libc::pollfd> = fds
+        .iter()
+        .map(|fd| libc::pollfd {
+            fd: *fd,
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+
+    if libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout) < 0 {
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(Error::IO(error));
+        }
+    }
+
+    Ok(pollfds
+        .iter()
+        .map(|p| p.revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0)
+        .collect())
+}
+
+/// Return the (master, slave) ends of a new pseudoterminal.
+#[cfg(test)]
+pub unsafe fn open_pty() -> Result<(std::fs::File, std::fs::File), Error> {
+    // <pty.h>
+    let (mut master, mut slave) = (0, 0);
+    c_err(
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+        ),
+        "unable to open pseudoterminal",
+    )?;
+
+    Ok((
+        std::os::fd::FromRawFd::from_raw_fd(master),
+        std::os::fd::FromRawFd::from_raw_fd(slave),
+    ))
+}
+
+/// Return a new inotify instance.
+///
+/// The descriptor is closed on exec.
+#[cfg(target_os = "linux")]
+pub unsafe fn inotify_init() -> Result<std::os::fd::OwnedFd, Error> {
+    // <sys/inotify.h>
+    let fd = libc::inotify_init1(libc::IN_CLOEXEC);
+    if fd < 0 {
+        return Err(Error::IO(std::io::Error::last_os_error()));
+    }
+
+    Ok(std::os::fd::FromRawFd::from_raw_fd(fd))
+}
+
+/// Watch the path for the events in `mask`, returning the watch descriptor.
+#[cfg(target_os = "linux")]
+pub unsafe fn inotify_add_watch(
+    fd: &std::os::fd::OwnedFd,
+    path: &std::path::Path,
+    mask: u32,
+) -> Result<libc::c_int, Error> {
+    use std::os::{fd::AsRawFd, unix::ffi::OsStrExt};
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| Error::FFI("path must not contain a nul byte"))?;
+
+    let wd = libc::inotify_add_watch(fd.as_raw_fd(), path.as_ptr(), mask);
+    if wd < 0 {
+        return Err(Error::IO(std::io::Error::last_os_error()));
+    }
+
+    Ok(wd)
+}
+
 /// Convert a C return code to a result.
 fn c_err(value: libc::c_int, reason: &'static str) -> Result<(), Error> {
     if value != 0 {
@@ -31,10 +259,57 @@ unsafe extern "C" fn handle_signal(
     _info: *mut libc::siginfo_t,
     _context: *mut libc::c_void,
 ) {
-    match signum {
-        libc::SIGWINCH => {
-            println!("Received SIGWINCH - Window Size Change");
+    if signum == libc::SIGWINCH {
+        HANDLING.fetch_add(1, Ordering::SeqCst);
+        for slot in &RESIZE_PIPES {
+            let fd = slot.load(Ordering::SeqCst);
+            if fd >= 0 {
+                libc::write(fd, [1u8].as_ptr() as *const libc::c_void, 1);
+            }
         }
-        _ => {}
+        HANDLING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::foreign::watch_resize;
+
+    /// Return the current handler of SIGWINCH.
+    fn handler() -> libc::sighandler_t {
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGWINCH, std::ptr::null(), &mut action);
+            action.sa_sigaction
+        }
+    }
+
+    #[test]
+    fn test_watch_resize() {
+        let previous = handler();
+
+        let (first, first_watch) = unsafe { watch_resize() }.unwrap();
+        let (second, second_watch) = unsafe { watch_resize() }.unwrap();
+        let (mut first, mut second) = (std::fs::File::from(first), std::fs::File::from(second));
+        let mut buffer = [0; 8];
+
+        // Every pipe is notified.
+        unsafe { libc::raise(libc::SIGWINCH) };
+        assert_eq!(first.read(&mut buffer).unwrap(), 1);
+        assert_eq!(second.read(&mut buffer).unwrap(), 1);
+
+        // Dropping a watch closes only its own pipe.
+        drop(first_watch);
+        assert_eq!(first.read(&mut buffer).unwrap(), 0);
+
+        unsafe { libc::raise(libc::SIGWINCH) };
+        assert_eq!(second.read(&mut buffer).unwrap(), 1);
+
+        // The previous handler is restored with the last watch.
+        assert_ne!(handler(), previous);
+        drop(second_watch);
+        assert_eq!(handler(), previous);
     }
 }
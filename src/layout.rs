@@ -0,0 +1,193 @@
+//! Arrange blocks of text, such as the views of several components.
+//!
+//! Blocks are measured in columns, ignoring escape sequences, so they may
+//! contain text drawn with `font`. The terminal is split into areas for
+//! each block with `Layout`.
+
+pub use self::constraint::{Constraint, Layout};
+pub use self::overlay::overlay;
+pub use self::rect::Rect;
+
+mod constraint;
+mod overlay;
+mod rect;
+
+use crate::ansi;
+use crate::font::Align;
+
+/// Describes how a block is placed within a taller area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Return the blocks placed side by side, from left to right.
+///
+/// Shorter blocks are filled with blank lines to the height of the tallest,
+/// and every line of a block is filled with spaces to the width of the block.
+///
+/// # Examples
+///
+/// ```
+/// use locket::layout::{join_horizontal, VerticalAlign};
+///
+/// let joined = join_horizontal(VerticalAlign::Bottom, &["a\nbb\nc", "| x"]);
+/// assert_eq!(joined, "a    \nbb   \nc | x");
+/// ```
+pub fn join_horizontal<T>(align: VerticalAlign, blocks: &[T]) -> String
+where
+    T: AsRef<str>,
+{
+    let blocks: Vec<Vec<&str>> = blocks.iter().map(|block| lines(block.as_ref())).collect();
+    let height = blocks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut joined = vec![String::new(); height];
+    for block in blocks {
+        let width = block
+            .iter()
+            .map(|line| ansi::width(line))
+            .max()
+            .unwrap_or(0);
+        let (top, _) = split(height - block.len(), align);
+
+        for (i, row) in joined.iter_mut().enumerate() {
+            let line = i.checked_sub(top).and_then(|i| block.get(i)).unwrap_or(&"");
+            row.push_str(&align_line(line, width, Align::Left));
+        }
+    }
+
+    joined.join("\n")
+}
+
+/// Return the blocks placed one above another, from top to bottom.
+///
+/// Every line is filled with spaces to the width of the widest block.
+///
+/// # Examples
+///
+/// ```
+/// use locket::font::Align;
+/// use locket::layout::join_vertical;
+///
+/// let joined = join_vertical(Align::Center, &["Title", "a\nbcd"]);
+/// assert_eq!(joined, "Title\n  a  \n bcd ");
+/// ```
+pub fn join_vertical<T>(align: Align, blocks: &[T]) -> String
+where
+    T: AsRef<str>,
+{
+    let lines: Vec<&str> = blocks
+        .iter()
+        .flat_map(|block| lines(block.as_ref()))
+        .collect();
+    let width = lines
+        .iter()
+        .map(|line| ansi::width(line))
+        .max()
+        .unwrap_or(0);
+
+    let lines: Vec<String> = lines
+        .into_iter()
+        .map(|line| align_line(line, width, align))
+        .collect();
+
+    lines.join("\n")
+}
+
+/// Return the block placed within an area of `width` columns and `height`
+/// lines, which is filled with spaces around it.
+///
+/// A block that is larger than the area is not cut.
+///
+/// # Examples
+///
+/// Center a message in the terminal:
+///
+/// ```
+/// use locket::font::Align;
+/// use locket::layout::{place, VerticalAlign};
+///
+/// let placed = place(5, 3, Align::Center, VerticalAlign::Center, "hi");
+/// assert_eq!(placed, "     \n hi  \n     ");
+/// ```
+pub fn place<T>(
+    width: usize,
+    height: usize,
+    align: Align,
+    vertical_align: VerticalAlign,
+    block: T,
+) -> String
+where
+    T: AsRef<str>,
+{
+    let block = lines(block.as_ref());
+    let width = block
+        .iter()
+        .map(|line| ansi::width(line))
+        .max()
+        .unwrap_or(0)
+        .max(width);
+    let (top, bottom) = split(height.saturating_sub(block.len()), vertical_align);
+
+    let blank = " ".repeat(width);
+    let mut placed = Vec::with_capacity(top + block.len() + bottom);
+    placed.extend(std::iter::repeat_n(blank.clone(), top));
+    placed.extend(block.iter().map(|line| align_line(line, width, align)));
+    placed.extend(std::iter::repeat_n(blank, bottom));
+
+    placed.join("\n")
+}
+
+/// Return the line filled with spaces to `width` columns, placed by `align`.
+pub(crate) fn align_line(line: &str, width: usize, align: Align) -> String {
+    let gap = width.saturating_sub(ansi::width(line));
+    let (left, right) = match align {
+        Align::Left => (0, gap),
+        Align::Center => (gap / 2, gap - gap / 2),
+        Align::Right => (gap, 0),
+    };
+
+    format!("{}{}{}", " ".repeat(left), line, " ".repeat(right))
+}
+
+/// Return the space above and below a block, from the space around it.
+fn split(space: usize, align: VerticalAlign) -> (usize, usize) {
+    match align {
+        VerticalAlign::Top => (0, space),
+        VerticalAlign::Center => (space / 2, space - space / 2),
+        VerticalAlign::Bottom => (space, 0),
+    }
+}
+
+/// Return the lines of a block, which has at least one line.
+pub(crate) fn lines(block: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = block.lines().collect();
+    if lines.is_empty() {
+        lines.push("");
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::{Align, Style};
+    use crate::layout::{join_horizontal, join_vertical, VerticalAlign};
+
+    #[test]
+    fn test_join_styled() {
+        // Escape sequences take no columns.
+        let bold = Style::new().bold().render("ab");
+        assert_eq!(
+            join_horizontal(VerticalAlign::Center, &[bold.as_str(), "x\r\ny\r\nz"]),
+            format!("  x\n{}y\n  z", bold)
+        );
+        assert_eq!(
+            join_vertical(Align::Right, &[bold.as_str(), "日本"]),
+            format!("  {}\n日本", bold)
+        );
+    }
+}
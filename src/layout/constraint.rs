@@ -0,0 +1,200 @@
+use crate::layout::Rect;
+
+/// Describes the size of one part of an area split by a `Layout`.
+///
+/// Sizes are in columns for a horizontal layout, and in lines for a
+/// vertical layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// Exactly this size.
+    Length(u16),
+    /// This percentage of the area.
+    Percentage(u16),
+    /// At least this size, growing into space that no `Fill` takes.
+    Min(u16),
+    /// At most this size, and as large as possible within it.
+    Max(u16),
+    /// A share of the space left over by the other parts, in proportion to
+    /// the weight.
+    Fill(u16),
+}
+
+/// The direction that a `Layout` splits an area in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// Splits an area into parts, one for each constraint.
+///
+/// Parts are sized in order of precedence: first lengths, percentages and
+/// minimums, then maximums, then fills. When the area is too small for
+/// every part, the parts at the end are made smaller first.
+///
+/// # Examples
+///
+/// Split the terminal into a title line, a list, and a status line:
+///
+/// ```
+/// use locket::layout::{Constraint, Layout, Rect};
+///
+/// let area = Rect::new(0, 0, 80, 24);
+/// let parts = Layout::vertical([
+///     Constraint::Length(1),
+///     Constraint::Fill(1),
+///     Constraint::Length(1),
+/// ])
+/// .split(area);
+///
+/// assert_eq!(parts[1], Rect::new(0, 1, 80, 22));
+/// assert_eq!(parts[2], Rect::new(0, 23, 80, 1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+    /// Space between each part.
+    spacing: u16,
+}
+
+impl Layout {
+    /// Return a new instance of `Layout` that splits an area into rows.
+    pub fn vertical<I>(constraints: I) -> Self
+    where
+        I: IntoIterator<Item = Constraint>,
+    {
+        Self {
+            direction: Direction::Vertical,
+            constraints: constraints.into_iter().collect(),
+            spacing: 0,
+        }
+    }
+
+    /// Return a new instance of `Layout` that splits an area into columns.
+    pub fn horizontal<I>(constraints: I) -> Self
+    where
+        I: IntoIterator<Item = Constraint>,
+    {
+        Self {
+            direction: Direction::Horizontal,
+            constraints: constraints.into_iter().collect(),
+            spacing: 0,
+        }
+    }
+
+    /// Leave space between each part.
+    pub fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Return the parts of the area, one for each constraint, in order.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let total = match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        };
+        let gaps = self.spacing as usize * self.constraints.len().saturating_sub(1);
+        let available = (total as usize).saturating_sub(gaps);
+
+        let mut offset = 0;
+        solve(&self.constraints, available)
+            .into_iter()
+            .map(|size| {
+                let start = offset.min(total as usize) as u16;
+                offset += size + self.spacing as usize;
+
+                match self.direction {
+                    Direction::Horizontal => {
+                        Rect::new(area.x + start, area.y, size as u16, area.height)
+                    }
+                    Direction::Vertical => {
+                        Rect::new(area.x, area.y + start, area.width, size as u16)
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Return the size of each part, sharing `total` between the constraints.
+fn solve(constraints: &[Constraint], total: usize) -> Vec<usize> {
+    let mut remaining = total;
+    let mut take = |wanted: usize| {
+        let size = wanted.min(remaining);
+        remaining -= size;
+        size
+    };
+
+    // Sizes that are required are given in order, until there is no space.
+    let mut sizes: Vec<usize> = constraints
+        .iter()
+        .map(|constraint| match *constraint {
+            Constraint::Length(n) | Constraint::Min(n) => take(n as usize),
+            Constraint::Percentage(p) => take(total * p.min(100) as usize / 100),
+            Constraint::Max(_) | Constraint::Fill(_) => 0,
+        })
+        .collect();
+
+    for (size, constraint) in sizes.iter_mut().zip(constraints) {
+        if let Constraint::Max(n) = *constraint {
+            *size = take(n as usize);
+        }
+    }
+
+    // Space that is left is shared by fills, or by minimums if there are none.
+    let weights: Vec<usize> = constraints
+        .iter()
+        .map(|constraint| match *constraint {
+            Constraint::Fill(weight) => weight as usize,
+            _ => 0,
+        })
+        .collect();
+    let weights = if weights.iter().any(|weight| *weight > 0) {
+        weights
+    } else {
+        constraints
+            .iter()
+            .map(|constraint| usize::from(matches!(constraint, Constraint::Min(_))))
+            .collect()
+    };
+
+    // Each share is rounded from the running total, so no space is lost.
+    let sum: usize = weights.iter().sum();
+    let share = |weight: usize| (remaining * weight).checked_div(sum).unwrap_or(0);
+    let mut weight_before = 0;
+    for (size, weight) in sizes.iter_mut().zip(weights) {
+        let start = share(weight_before);
+        weight_before += weight;
+        *size += share(weight_before) - start;
+    }
+
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::constraint::solve;
+    use crate::layout::{Constraint, Layout, Rect};
+
+    #[test]
+    fn test_solve() {
+        use Constraint::*;
+
+        assert_eq!(solve(&[Length(2), Fill(1), Fill(2)], 11), vec![2, 3, 6]);
+        assert_eq!(solve(&[Percentage(50), Min(1), Max(3)], 10), vec![5, 2, 3]);
+        assert_eq!(solve(&[Max(3), Max(3)], 4), vec![3, 1]);
+
+        // Parts at the end shrink first.
+        assert_eq!(solve(&[Length(3), Length(3), Fill(1)], 4), vec![3, 1, 0]);
+    }
+
+    #[test]
+    fn test_split_spacing() {
+        let parts = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)])
+            .spacing(1)
+            .split(Rect::new(2, 3, 9, 4));
+
+        assert_eq!(parts, vec![Rect::new(2, 3, 4, 4), Rect::new(7, 3, 4, 4)]);
+    }
+}
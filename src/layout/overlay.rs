@@ -0,0 +1,281 @@
+use crate::ansi::{self, Token};
+use crate::font::Align;
+use crate::layout::{align_line, lines};
+
+/// Return the `over` block drawn on top of the `under` block, with its top
+/// left corner at column `x` and line `y`.
+///
+/// The text of `under` that is covered is removed, including any part of a
+/// wide character, which is replaced by spaces. Styles and hyperlinks of
+/// `under` end where `over` starts, and are applied again where it ends.
+/// Every line of `over` is filled with spaces to the width of the block, so
+/// it covers a rectangle.
+///
+/// # Examples
+///
+/// Draw a dialog over the middle of a view:
+///
+/// ```
+/// use locket::font::{Border, Style};
+/// use locket::layout::overlay;
+///
+/// let view = "..........\n..........\n..........\n..........";
+/// let dialog = Style::new().border(Border::ASCII).render("ok?");
+///
+/// assert_eq!(
+///     overlay(view, &dialog, 3, 1),
+///     "..........\n...+---+..\n...|ok?|..\n...+---+.."
+/// );
+/// ```
+pub fn overlay(under: &str, over: &str, x: usize, y: usize) -> String {
+    let over = lines(over);
+    let width = over.iter().map(|line| ansi::width(line)).max().unwrap_or(0);
+
+    let mut composed: Vec<String> = under.lines().map(str::to_string).collect();
+    if composed.len() < y + over.len() {
+        composed.resize(y + over.len(), String::new());
+    }
+
+    for (i, line) in over.into_iter().enumerate() {
+        let line = align_line(line, width, Align::Left);
+        composed[y + i] = overlay_line(&composed[y + i], &line, x, width);
+    }
+
+    composed.join("\n")
+}
+
+/// Return the line `over`, which is `width` columns wide, drawn on top of
+/// the line `under` at column `x`.
+fn overlay_line(under: &str, over: &str, x: usize, width: usize) -> String {
+    let (mut line, open) = columns_before(under, x);
+    line.push_str(&open.close());
+
+    line.push_str(over);
+    let mut state = State::default();
+    for token in ansi::tokens(over) {
+        if let Token::Escape(sequence) = token {
+            state.apply(sequence);
+        }
+    }
+    line.push_str(&state.close());
+
+    line.push_str(&columns_after(under, x + width));
+
+    line
+}
+
+/// Return the columns of the line before column `x`, filled with spaces if
+/// the line is shorter, and the styles and hyperlink open at the end.
+fn columns_before(line: &str, x: usize) -> (String, State<'_>) {
+    let mut before = String::new();
+    let mut column = 0;
+    let mut state = State::default();
+
+    for token in ansi::tokens(line) {
+        if column >= x {
+            break;
+        }
+
+        match token {
+            Token::Escape(sequence) => {
+                before.push_str(sequence);
+                state.apply(sequence);
+            }
+            Token::Text(grapheme) => {
+                let width = ansi::advance(column, grapheme);
+                if column + width > x {
+                    // A wide character that is cut in half.
+                    break;
+                }
+
+                before.push_str(grapheme);
+                column += width;
+            }
+        }
+    }
+
+    // The spaces that fill the line are not styled or linked.
+    if column < x {
+        before.push_str(&state.close());
+        state = State::default();
+        before.push_str(&" ".repeat(x - column));
+    }
+
+    (before, state)
+}
+
+/// Return the columns of the line from column `x`, beginning with the
+/// escape sequences of the covered columns, and the styles and hyperlink
+/// that apply there.
+fn columns_after(line: &str, x: usize) -> String {
+    let mut after = String::new();
+    let mut column = 0;
+    let mut state = State::default();
+    let mut started = false;
+
+    for token in ansi::tokens(line) {
+        match token {
+            Token::Escape(sequence) if started => after.push_str(sequence),
+            Token::Escape(sequence) => {
+                // Sequences that take no columns, and hold no state, are kept.
+                if !state.apply(sequence) {
+                    after.push_str(sequence);
+                }
+            }
+            Token::Text(grapheme) => {
+                let width = ansi::advance(column, grapheme);
+                if !started && column + width > x {
+                    started = true;
+                    after.push_str(&state.open());
+
+                    // The covered half of a wide character is replaced.
+                    if column < x {
+                        after.push_str(&" ".repeat(column + width - x));
+                        column += width;
+                        continue;
+                    }
+                }
+
+                if started {
+                    after.push_str(grapheme);
+                }
+                column += width;
+            }
+        }
+    }
+
+    after
+}
+
+/// The styles and hyperlink that are open at a column of a line.
+#[derive(Debug, Default)]
+struct State<'a> {
+    /// Select Graphic Rendition sequences applied since the last reset.
+    styles: Vec<&'a str>,
+    /// The `OSC 8` sequence that opened the current hyperlink.
+    link: Option<&'a str>,
+}
+
+impl<'a> State<'a> {
+    /// Update the state with the escape sequence, returning false if it
+    /// does not change the state.
+    fn apply(&mut self, sequence: &'a str) -> bool {
+        if ansi::is_reset(sequence) {
+            self.styles.clear();
+        } else if is_sgr(sequence) {
+            self.styles.push(sequence);
+        } else if let Some(open) = is_hyperlink(sequence) {
+            self.link = open.then_some(sequence);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// Return the sequences that apply the state again.
+    fn open(&self) -> String {
+        self.link
+            .into_iter()
+            .chain(self.styles.iter().copied())
+            .collect()
+    }
+
+    /// Return the sequences that end the state.
+    fn close(&self) -> String {
+        let mut close = String::new();
+        if !self.styles.is_empty() {
+            close.push_str("\x1B[0m");
+        }
+        if self.link.is_some() {
+            close.push_str(CLOSE_LINK);
+        }
+
+        close
+    }
+}
+
+/// Ends a hyperlink.
+const CLOSE_LINK: &str = "\x1B]8;;\x1B\\";
+
+/// Return true if the escape sequence is a Select Graphic Rendition.
+fn is_sgr(sequence: &str) -> bool {
+    sequence.starts_with("\x1B[") && sequence.ends_with('m')
+}
+
+/// Return whether the escape sequence opens a hyperlink, or `None` if it is
+/// not an `OSC 8` sequence.
+///
+/// A hyperlink is opened with a URI, and closed by an empty one.
+fn is_hyperlink(sequence: &str) -> Option<bool> {
+    let rest = sequence.strip_prefix("\x1B]8;")?;
+    let rest = rest
+        .strip_suffix('\x07')
+        .or_else(|| rest.strip_suffix("\x1B\\"))
+        .unwrap_or(rest);
+    let (_, uri) = rest.split_once(';')?;
+
+    Some(!uri.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::overlay;
+
+    #[test]
+    fn test_overlay_styles() {
+        // The style under the block is applied again after it.
+        let under = "\x1B[31mredred\x1B[0m";
+        assert_eq!(
+            overlay(under, "XY", 2, 0),
+            "\x1B[31mre\x1B[0mXY\x1B[31med\x1B[0m"
+        );
+
+        // Styles of the block end with it.
+        assert_eq!(
+            overlay("abcd", "\x1B[1mX\x1B[0m\x1B[1m", 1, 0),
+            "a\x1B[1mX\x1B[0m\x1B[1m\x1B[0mcd"
+        );
+    }
+
+    #[test]
+    fn test_overlay_reset() {
+        // The style is reset within the covered columns, so nothing is
+        // applied again after the block.
+        assert_eq!(
+            overlay("\x1B[31mab\x1B[0mcd", "XY", 1, 0),
+            "\x1B[31ma\x1B[0mXYd"
+        );
+
+        // The spaces that fill a short line are not styled.
+        assert_eq!(overlay("\x1B[1mab", "X", 4, 0), "\x1B[1mab\x1B[0m  X");
+    }
+
+    #[test]
+    fn test_overlay_hyperlink() {
+        let open = "\x1B]8;;https://example.com\x1B\\";
+        let close = "\x1B]8;;\x1B\\";
+
+        // The hyperlink is closed before the block, and opened again after.
+        assert_eq!(
+            overlay(&format!("{open}link{close}"), "X", 1, 0),
+            format!("{open}l{close}X{open}nk{close}")
+        );
+
+        // A hyperlink that ends within the covered columns is not opened again.
+        assert_eq!(
+            overlay(&format!("{open}ab{close}cd"), "XY", 1, 0),
+            format!("{open}a{close}XYd")
+        );
+    }
+
+    #[test]
+    fn test_overlay_wide() {
+        // Halves of wide characters that are covered become spaces.
+        assert_eq!(overlay("日本語", "ab", 1, 0), " ab 語");
+        assert_eq!(overlay("日本語", "abc", 2, 0), "日abc ");
+
+        // A block beyond the text extends it.
+        assert_eq!(overlay("a", "b\nc", 2, 1), "a\n  b\n  c");
+    }
+}
@@ -0,0 +1,32 @@
+/// Describes an area of the terminal, in cells.
+///
+/// The area of the whole terminal is returned by `ResizeEvent::area`, and
+/// split into smaller areas with `Layout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    /// The column of the left edge.
+    pub x: u16,
+    /// The line of the top edge.
+    pub y: u16,
+    /// The number of columns.
+    pub width: u16,
+    /// The number of lines.
+    pub height: u16,
+}
+
+impl Rect {
+    /// Return a new instance of `Rect`.
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Return true if the area has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
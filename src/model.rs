@@ -1,7 +1,16 @@
-pub use self::command::{batch, exit, BatchMessage, Command, ExitMessage, Message};
+#[cfg(target_os = "linux")]
+pub(crate) use self::command::closable_stream;
+pub(crate) use self::command::ClosableStreamMessage;
+pub use self::command::{
+    batch, exit, forward, println, set_directory, set_title, stream, BatchMessage, Command,
+    DirectoryMessage, ExitMessage, Message, PrintMessage, StreamMessage, TitleMessage,
+};
 
 mod command;
 
+use crate::buffer::Buffer;
+use crate::cursor::Cursor;
+
 /// Defines an executable Locket model.
 pub trait Model {
     /// Called a single time when the model is first executed.
@@ -14,4 +23,28 @@ pub trait Model {
 
     /// Returns the interfaceCalled after `update`
     fn view(&self) -> String;
+
+    /// Called instead of `view` when executing with `Program::with_cells`.
+    ///
+    /// The buffer is empty, and the size of the terminal. By default,
+    /// the text returned by `view` is drawn from the top left corner.
+    fn draw(&self, buffer: &mut Buffer) {
+        buffer.set_ansi(0, 0, self.view());
+    }
+
+    /// Called after `view` or `draw`, to place the terminal cursor.
+    ///
+    /// The position is relative to the top left corner of the view. The
+    /// cursor is hidden when `None` is returned, which is the default.
+    fn cursor(&self) -> Option<Cursor> {
+        None
+    }
+
+    /// Called a single time after the application has exited.
+    ///
+    /// The returned text is written to `stdout` after the terminal has been
+    /// restored, so the result of the program can be used in a pipeline.
+    fn result(&self) -> Option<String> {
+        None
+    }
 }
@@ -1,4 +1,10 @@
-pub use self::message::{batch, exit, BatchMessage, ExitMessage, Message};
+#[cfg(target_os = "linux")]
+pub(crate) use self::message::closable_stream;
+pub(crate) use self::message::ClosableStreamMessage;
+pub use self::message::{
+    batch, exit, forward, println, set_directory, set_title, stream, BatchMessage,
+    DirectoryMessage, ExitMessage, Message, PrintMessage, StreamMessage, TitleMessage,
+};
 
 mod message;
 
@@ -1,3 +1,6 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
 use super::Command;
 
 /// A command that will exit the Locket application.
@@ -14,6 +17,74 @@ pub fn exit() -> Option<Message> {
 /// and extract any required information.
 pub type Message = Box<dyn std::any::Any + Send>;
 
+/// A wrapper for text to be printed above the interface.
+pub struct PrintMessage(pub String);
+
+/// Print text above the interface.
+///
+/// The text is written to the terminal scrollback where it is preserved,
+/// even after the application has exited. Only the lines below it are
+/// redrawn on each update.
+///
+/// # Examples
+///
+/// Commit a finished item:
+///
+/// ```
+/// let cmd = locket::println("✓ installed foo");
+/// ```
+pub fn println<T>(text: T) -> Command
+where
+    T: Into<String>,
+{
+    let text = text.into();
+    Box::new(|| Some(Box::new(PrintMessage(text))))
+}
+
+/// A wrapper for the title of the terminal window.
+pub struct TitleMessage(pub String);
+
+/// Set the title of the terminal window or tab.
+///
+/// The previous title is restored when the application exits, in terminals
+/// that support saving it.
+///
+/// # Examples
+///
+/// Show the active screen in the tab name:
+///
+/// ```
+/// let cmd = locket::set_title("locket - settings");
+/// ```
+pub fn set_title<T>(title: T) -> Command
+where
+    T: Into<String>,
+{
+    let title = title.into();
+    Box::new(|| Some(Box::new(TitleMessage(title))))
+}
+
+/// A wrapper for the working directory reported to the terminal.
+pub struct DirectoryMessage(pub std::path::PathBuf);
+
+/// Report the current working directory to the terminal.
+///
+/// Terminals use the directory to open new tabs and windows in the same
+/// place. The working directory of the process is not changed.
+///
+/// # Examples
+///
+/// ```
+/// let cmd = locket::set_directory(std::env::temp_dir());
+/// ```
+pub fn set_directory<P>(path: P) -> Command
+where
+    P: Into<std::path::PathBuf>,
+{
+    let path = path.into();
+    Box::new(|| Some(Box::new(DirectoryMessage(path))))
+}
+
 /// A wrapper for `Vec<Command>`, representing a series of commands.
 pub struct BatchMessage(pub Vec<Command>);
 
@@ -21,3 +92,87 @@ pub struct BatchMessage(pub Vec<Command>);
 pub fn batch(cmds: Vec<Command>) -> Command {
     Box::new(|| Some(Box::new(BatchMessage(cmds))))
 }
+
+/// A wrapper for a function that may send any number of messages.
+///
+/// The function is given a sender, and runs on its own thread until it returns.
+pub struct StreamMessage(pub Box<dyn FnOnce(std::sync::mpsc::Sender<Message>) + Send>);
+
+/// Create a command that may deliver any number of messages over time.
+///
+/// Sending will fail once the application has exited, at which point
+/// the function should return.
+///
+/// # Examples
+///
+/// Count to three, delivering each number as a message:
+///
+/// ```
+/// let cmd = locket::stream(|tx| {
+///     for i in 0..3 {
+///         if tx.send(Box::new(i)).is_err() {
+///             return;
+///         }
+///     }
+/// });
+/// ```
+pub fn stream<F>(f: F) -> Command
+where
+    F: FnOnce(std::sync::mpsc::Sender<Message>) + Send + 'static,
+{
+    Box::new(|| Some(Box::new(StreamMessage(Box::new(f)))))
+}
+
+/// A wrapper for a function that may send any number of messages, and is
+/// told when the application has exited.
+///
+/// The flag is set once the application has exited, for functions that
+/// wait on something other than the sender.
+pub(crate) struct ClosableStreamMessage(
+    pub(crate) Box<dyn FnOnce(std::sync::mpsc::Sender<Message>, Arc<AtomicBool>) + Send>,
+);
+
+/// Create a command like `stream`, whose function is also given a flag that
+/// is set once the application has exited.
+#[cfg(target_os = "linux")]
+pub(crate) fn closable_stream<F>(f: F) -> Command
+where
+    F: FnOnce(std::sync::mpsc::Sender<Message>, Arc<AtomicBool>) + Send + 'static,
+{
+    Box::new(|| Some(Box::new(ClosableStreamMessage(Box::new(f)))))
+}
+
+/// Create a command that forwards every value received on the receiver
+/// as a message.
+///
+/// The command ends when all senders have been dropped.
+///
+/// # Examples
+///
+/// Forward results from a worker thread:
+///
+/// ```
+/// let (tx, rx) = std::sync::mpsc::channel::<u64>();
+///
+/// std::thread::spawn(move || {
+///     for i in 0..10 {
+///         tx.send(i * i).unwrap();
+///     }
+/// });
+///
+/// // Each `u64` is delivered to `update`, where it may be read with
+/// // `message.downcast_ref::<u64>()`.
+/// let cmd = locket::forward(rx);
+/// ```
+pub fn forward<T>(receiver: std::sync::mpsc::Receiver<T>) -> Command
+where
+    T: Send + 'static,
+{
+    stream(move |tx| {
+        for value in receiver {
+            if tx.send(Box::new(value)).is_err() {
+                return;
+            }
+        }
+    })
+}
@@ -0,0 +1,255 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use crate::{stream, Command, Message};
+
+/// Source of the next process id.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Describes the stream that a line of output was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// The standard output of the process.
+    Stdout,
+    /// The standard error of the process.
+    Stderr,
+}
+
+/// A message carrying a single line of output from a process.
+///
+/// The trailing line ending is removed, and invalid UTF-8 is replaced.
+pub struct OutputMessage {
+    /// Id of the process, matching `Handle::id`.
+    pub id: usize,
+    /// The stream that the line was read from.
+    pub output: Output,
+    /// The line of output.
+    pub line: String,
+}
+
+/// A message delivered a single time, after a process has exited.
+///
+/// Contains an error if the process could not be started or waited on.
+pub struct StatusMessage {
+    /// Id of the process, matching `Handle::id`.
+    pub id: usize,
+    /// The exit status of the process.
+    pub status: std::io::Result<ExitStatus>,
+}
+
+/// A handle to a process started by `spawn`.
+///
+/// Handles are cheap to clone, and may be used to kill the process from `update`.
+#[derive(Clone)]
+pub struct Handle {
+    /// Unique id of the process, repeated in each message it produces.
+    id: usize,
+    /// The running process.
+    ///
+    /// This is `None` until the command is executed.
+    child: Arc<Mutex<Option<Child>>>,
+    /// Set when `kill` is called, so a process not yet started is killed immediately.
+    killed: Arc<AtomicBool>,
+}
+
+impl Handle {
+    /// Return the id of the process.
+    ///
+    /// This is not the operating system pid, it is only used to tell apart
+    /// the messages of several processes.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Kill the process.
+    ///
+    /// If the command has not been executed yet, the process is killed as soon
+    /// as it starts. A `StatusMessage` is still delivered.
+    pub fn kill(&self) -> std::io::Result<()> {
+        self.killed.store(true, Ordering::SeqCst);
+
+        match self.child.lock().unwrap().as_mut() {
+            Some(child) => child.kill(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Return a command that will spawn a process and stream its output.
+///
+/// Each line written to stdout or stderr is delivered as an `OutputMessage`,
+/// followed by a `StatusMessage` when the process exits. The process is not
+/// started until the command is executed.
+///
+/// The standard input of the process is set to null, so that it does not
+/// compete with Locket for terminal input.
+///
+/// # Examples
+///
+/// ```
+/// let mut cargo = std::process::Command::new("cargo");
+/// cargo.arg("build");
+///
+/// let (cmd, handle) = locket::process::spawn(cargo);
+///
+/// // Return `cmd` from `init` or `update`, and keep `handle` in the model
+/// // to match messages by `handle.id()` or call `handle.kill()`.
+/// ```
+pub fn spawn(command: std::process::Command) -> (Command, Handle) {
+    let handle = Handle {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        child: Arc::new(Mutex::new(None)),
+        killed: Arc::new(AtomicBool::new(false)),
+    };
+
+    let inner = handle.clone();
+    let cmd = stream(move |tx| run(command, inner, tx));
+
+    (cmd, handle)
+}
+
+/// Start the process, and send messages until it has exited.
+fn run(mut command: std::process::Command, handle: Handle, tx: Sender<Message>) {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            let _ = tx.send(Box::new(StatusMessage {
+                id: handle.id,
+                status: Err(error),
+            }));
+            return;
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    {
+        // Check under the lock, so a concurrent `Handle::kill` cannot be missed.
+        let mut guard = handle.child.lock().unwrap();
+        if handle.killed.load(Ordering::SeqCst) {
+            let _ = child.kill();
+        }
+        *guard = Some(child);
+    }
+
+    let stderr_thread = stderr.map(|stderr| {
+        let tx = tx.clone();
+        std::thread::spawn(move || read_lines(stderr, handle.id, Output::Stderr, tx))
+    });
+    if let Some(stdout) = stdout {
+        read_lines(stdout, handle.id, Output::Stdout, tx.clone());
+    }
+    if let Some(thread) = stderr_thread {
+        let _ = thread.join();
+    }
+
+    // Poll rather than block on `wait`, so the lock is free for `Handle::kill`.
+    let status = loop {
+        if let Some(child) = handle.child.lock().unwrap().as_mut() {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {}
+                Err(error) => break Err(error),
+            }
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+
+    let _ = tx.send(Box::new(StatusMessage {
+        id: handle.id,
+        status,
+    }));
+}
+
+/// Send each line from the reader as an `OutputMessage`.
+///
+/// Returns when the reader is exhausted, or the application has exited.
+fn read_lines(reader: impl Read, id: usize, output: Output, tx: Sender<Message>) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+
+        let line = String::from_utf8_lossy(&buffer)
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+
+        if tx
+            .send(Box::new(OutputMessage { id, output, line }))
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::StreamMessage;
+    use crate::process::{spawn, Output, OutputMessage, StatusMessage};
+
+    #[test]
+    fn test_spawn() {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", "echo one; echo two >&2; echo three; exit 3"]);
+
+        let (cmd, handle) = spawn(command);
+        let stream = cmd().unwrap().downcast::<StreamMessage>().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        (stream.0)(tx);
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut status = None;
+        for message in rx.try_iter() {
+            if let Some(output) = message.downcast_ref::<OutputMessage>() {
+                assert_eq!(output.id, handle.id());
+                match output.output {
+                    Output::Stdout => stdout.push(output.line.clone()),
+                    Output::Stderr => stderr.push(output.line.clone()),
+                }
+            } else if let Ok(message) = message.downcast::<StatusMessage>() {
+                assert!(status.is_none(), "status must be delivered once");
+                status = Some(message.status.unwrap());
+            }
+        }
+
+        assert_eq!(stdout, vec!["one", "three"]);
+        assert_eq!(stderr, vec!["two"]);
+        assert_eq!(status.unwrap().code(), Some(3));
+    }
+
+    #[test]
+    fn test_kill() {
+        let mut command = std::process::Command::new("sleep");
+        command.arg("10");
+
+        let (cmd, handle) = spawn(command);
+        let stream = cmd().unwrap().downcast::<StreamMessage>().unwrap();
+
+        // Kill before the process has started.
+        handle.kill().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        (stream.0)(tx);
+
+        let message = rx.recv().unwrap().downcast::<StatusMessage>().unwrap();
+        assert!(!message.status.unwrap().success());
+    }
+}
@@ -0,0 +1,471 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::backend::{Backend, CrosstermBackend};
+use crate::font::{self, Profile};
+use crate::{buffer::Buffer, event, model, renderer::Renderer, Model};
+
+/// Describes where the interface is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Target {
+    /// Draw to standard output.
+    #[default]
+    Stdout,
+    /// Draw to standard error, leaving standard output clean.
+    Stderr,
+    /// Draw directly to the controlling terminal, `/dev/tty`.
+    ///
+    /// This works even when both standard output and standard error
+    /// are redirected.
+    Tty,
+}
+
+/// Describes what happens to the last frame when the application exits.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OnExit {
+    /// Leave the last frame on screen.
+    #[default]
+    Keep,
+    /// Clear the last frame.
+    ///
+    /// Text printed with `println` is preserved, so a summary that depends
+    /// on the state of the model can be printed before exiting.
+    Clear,
+    /// Clear the last frame, and print the given text in its place.
+    Replace(String),
+}
+
+/// Configures and executes a model.
+///
+/// # Examples
+///
+/// Read lines piped to the program, as in `ls | picker`:
+///
+/// ```no_run
+/// # struct Picker;
+/// # impl locket::Model for Picker {
+/// #     fn update(&mut self, _: &locket::Message) -> Option<locket::Command> { None }
+/// #     fn view(&self) -> String { String::new() }
+/// # }
+/// locket::Program::new(Picker).with_stdin().run().unwrap();
+/// ```
+///
+/// Draw to the terminal and write the result to `stdout`, as in `vim $(picker)`:
+///
+/// ```no_run
+/// # struct Picker;
+/// # impl locket::Model for Picker {
+/// #     fn update(&mut self, _: &locket::Message) -> Option<locket::Command> { None }
+/// #     fn view(&self) -> String { String::new() }
+/// # }
+/// use locket::{Program, Target};
+///
+/// Program::new(Picker).with_target(Target::Tty).run().unwrap();
+/// ```
+pub struct Program<M>
+where
+    M: Model,
+{
+    /// The model to execute.
+    model: M,
+    /// Deliver piped standard input to the model.
+    stdin: bool,
+    /// Where the interface is drawn.
+    target: Target,
+    /// What happens to the last frame on exit.
+    on_exit: OnExit,
+    /// Draw frames with `Model::draw` into a cell buffer.
+    cells: bool,
+    /// The terminal to run on, if not the default.
+    backend: Option<Box<dyn Backend>>,
+    /// The colors that the terminal can display, if not detected.
+    profile: Option<Profile>,
+    /// Whether the terminal has a dark background, if not detected.
+    dark_background: Option<bool>,
+}
+
+impl<M> Program<M>
+where
+    M: Model,
+{
+    /// Return a new instance of `Program`.
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            stdin: false,
+            target: Target::default(),
+            on_exit: OnExit::default(),
+            cells: false,
+            backend: None,
+            profile: None,
+            dark_background: None,
+        }
+    }
+
+    /// Deliver each line of piped standard input as a `StdinEvent`,
+    /// followed by a `StdinClosedEvent`.
+    ///
+    /// Keyboard input is always read from the terminal, so this does not
+    /// interfere with key events. Nothing is delivered when standard input
+    /// is a terminal.
+    pub fn with_stdin(self) -> Self {
+        Self {
+            stdin: true,
+            ..self
+        }
+    }
+
+    /// Set where the interface is drawn by the default backend.
+    ///
+    /// The default is `Target::Stdout`. Use another target to keep `stdout`
+    /// free for the text returned by `Model::result`.
+    pub fn with_target(self, target: Target) -> Self {
+        Self { target, ..self }
+    }
+
+    /// Set what happens to the last frame when the application exits.
+    ///
+    /// The default is `OnExit::Keep`.
+    pub fn on_exit(self, on_exit: OnExit) -> Self {
+        Self { on_exit, ..self }
+    }
+
+    /// Draw each frame with `Model::draw` into a cell buffer, and only write
+    /// the cells that changed since the last frame.
+    ///
+    /// The interface takes up the whole terminal, using the alternate screen,
+    /// which is left on exit. Text printed with `println` is not shown, and
+    /// the last frame is not kept on exit.
+    pub fn with_cells(self) -> Self {
+        Self {
+            cells: true,
+            ..self
+        }
+    }
+
+    /// Run on the given terminal, rather than a `CrosstermBackend` drawing
+    /// to the target.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct App;
+    /// # impl locket::Model for App {
+    /// #     fn update(&mut self, _: &locket::Message) -> Option<locket::Command> { None }
+    /// #     fn view(&self) -> String { String::new() }
+    /// # }
+    /// use locket::{backend::LibcBackend, Program};
+    ///
+    /// Program::new(App)
+    ///     .with_backend(LibcBackend::new().unwrap())
+    ///     .run()
+    ///     .unwrap();
+    /// ```
+    pub fn with_backend(self, backend: impl Backend + 'static) -> Self {
+        Self {
+            backend: Some(Box::new(backend)),
+            ..self
+        }
+    }
+
+    /// Set the colors that the terminal can display, instead of detecting
+    /// them with `Profile::detect`.
+    ///
+    /// Every frame is downsampled to the profile as it is written, so
+    /// programs running at the same time may draw with different profiles.
+    pub fn with_profile(self, profile: Profile) -> Self {
+        Self {
+            profile: Some(profile),
+            ..self
+        }
+    }
+
+    /// Set whether the terminal has a dark background, instead of asking
+    /// the terminal.
+    ///
+    /// This chooses which color of a `Color::Adaptive` is drawn while the
+    /// program runs, see `font::has_dark_background`.
+    pub fn with_dark_background(self, dark: bool) -> Self {
+        Self {
+            dark_background: Some(dark),
+            ..self
+        }
+    }
+
+    /// Execute the model, returning once it has exited.
+    ///
+    /// The text returned by `Model::result` is then written to `stdout`.
+    ///
+    /// When the interface would be drawn to a file or pipe, such as the log
+    /// of a CI job, no events are read and no escape sequences are written.
+    /// Text printed with `println` is written as it arrives, and the final
+    /// view is written once on exit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be configured, or if standard
+    /// input is piped and no controlling terminal is available to read keys from.
+    pub fn run(self) -> std::io::Result<()> {
+        let mut model = self.model;
+        let mut backend = match self.backend {
+            Some(backend) => backend,
+            None => Box::new(CrosstermBackend::new(self.target)?),
+        };
+        let terminal = backend.is_terminal();
+        let profile = self.profile.unwrap_or_else(|| Profile::detect(terminal));
+        let mut dark_background = self.dark_background;
+        let cells = self.cells && terminal;
+
+        let (message_tx, message_rx) = std::sync::mpsc::channel::<model::Message>();
+        let message_tx_2 = message_tx.clone();
+
+        let (command_tx, command_rx) = std::sync::mpsc::channel::<model::Command>();
+
+        // Set once the application has exited, to stop streams that wait on
+        // something other than the sender.
+        let closed = CloseOnDrop(Arc::new(AtomicBool::new(false)));
+        let closed_2 = closed.0.clone();
+
+        if self.stdin && !std::io::stdin().is_terminal() {
+            let stdin_tx = message_tx.clone();
+            std::thread::spawn(move || read_stdin(stdin_tx));
+        }
+
+        let mut renderer = if terminal {
+            // The background is detected before events are read, so that the
+            // answer of the terminal is not taken as keys.
+            backend.enable_raw_mode()?;
+            if dark_background.is_none() {
+                dark_background = font::detect_background(&mut *backend);
+            }
+
+            let setup = backend
+                .events()
+                .and_then(|events| Ok((events, backend.size()?)));
+            let (mut events, size) = match setup {
+                Ok(setup) => setup,
+                Err(error) => {
+                    // Leave the terminal as it was found.
+                    let _ = backend.disable_raw_mode();
+                    return Err(error);
+                }
+            };
+
+            // Models are given the size of the terminal before the first event.
+            let _ = message_tx.send(Box::new(event::ResizeEvent(size.0, size.1)));
+
+            std::thread::spawn(move || loop {
+                let message: model::Message = match events.read() {
+                    Ok(Some(crossterm::event::Event::Key(event))) => Box::new(event),
+                    Ok(Some(crossterm::event::Event::Mouse(event))) => Box::new(event),
+                    Ok(Some(crossterm::event::Event::Resize(x, y))) => {
+                        Box::new(event::ResizeEvent(x, y))
+                    }
+                    Ok(Some(_)) => continue,
+                    Ok(None) | Err(_) => return,
+                };
+
+                if message_tx.send(message).is_err() {
+                    return;
+                }
+            });
+
+            Renderer::new(backend, size).with_profile(profile)
+        } else {
+            Renderer::plain(backend)
+        };
+
+        // Adaptive colors rendered by the model, on this thread, are drawn for
+        // the background of this program's terminal.
+        let _background =
+            font::BackgroundScope::enter(dark_background.unwrap_or_else(font::has_dark_background));
+
+        std::thread::spawn(move || loop {
+            let cmd = match command_rx.recv() {
+                Ok(cmd) => cmd,
+                Err(_) => return,
+            };
+
+            let msg_tx2 = message_tx_2.clone();
+            let closed = closed_2.clone();
+            std::thread::spawn(move || match cmd() {
+                Some(msg) if msg.is::<model::StreamMessage>() => {
+                    let stream = msg.downcast::<model::StreamMessage>().unwrap();
+                    (stream.0)(msg_tx2);
+                }
+                Some(msg) if msg.is::<model::ClosableStreamMessage>() => {
+                    let stream = msg.downcast::<model::ClosableStreamMessage>().unwrap();
+                    (stream.0)(msg_tx2, closed);
+                }
+                Some(msg) => {
+                    // The application may have exited while the command was running.
+                    let _ = msg_tx2.send(msg);
+                }
+                None => {}
+            });
+        });
+
+        if let Some(cmd) = model.init() {
+            command_tx.send(cmd).unwrap();
+        }
+        if terminal {
+            initialize(renderer.backend())?;
+        }
+        if cells {
+            crossterm::execute!(
+                renderer.backend(),
+                crossterm::terminal::EnterAlternateScreen
+            )?;
+        }
+        render(&mut renderer, &model, cells)?;
+
+        loop {
+            let message = message_rx.recv().unwrap();
+            if message.is::<model::ExitMessage>() {
+                break;
+            } else if message.is::<model::BatchMessage>() {
+                let batch = message.downcast::<model::BatchMessage>().unwrap();
+                for cmd in batch.0 {
+                    command_tx.send(cmd).unwrap();
+                }
+            } else if let Some(title) = message.downcast_ref::<model::TitleMessage>() {
+                renderer.set_title(&title.0)?;
+                continue;
+            } else if let Some(directory) = message.downcast_ref::<model::DirectoryMessage>() {
+                renderer.set_directory(&directory.0)?;
+                continue;
+            } else if message.is::<model::PrintMessage>() {
+                let print = message.downcast::<model::PrintMessage>().unwrap();
+                if !cells {
+                    renderer.print(print.0)?;
+                }
+            } else {
+                if let Some(resize) = message.downcast_ref::<event::ResizeEvent>() {
+                    renderer.resize(resize.0, resize.1);
+                }
+                if let Some(cmd) = model.update(&message) {
+                    command_tx.send(cmd).unwrap();
+                }
+            }
+
+            render(&mut renderer, &model, cells)?;
+        }
+        drop(closed);
+
+        if cells {
+            crossterm::execute!(
+                renderer.backend(),
+                crossterm::terminal::LeaveAlternateScreen
+            )?;
+            if let OnExit::Replace(summary) = self.on_exit {
+                renderer.print(summary)?;
+            }
+        } else {
+            match self.on_exit {
+                OnExit::Keep => renderer.finish()?,
+                OnExit::Clear => renderer.clear()?,
+                OnExit::Replace(summary) => renderer.print(summary)?,
+            }
+        }
+        if terminal {
+            renderer.reset_cursor()?;
+            renderer.restore_title()?;
+            deinitialize(renderer.backend())?;
+        }
+
+        if let Some(mut result) = model.result() {
+            if !result.ends_with('\n') {
+                result.push('\n');
+            }
+
+            // The result may be written to a pipe, rather than the terminal.
+            let profile = self
+                .profile
+                .unwrap_or_else(|| Profile::detect(std::io::stdout().is_terminal()));
+
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(profile.downsample(&result).as_bytes())?;
+            stdout.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draw the next frame of the model.
+fn render(renderer: &mut Renderer, model: &impl Model, cells: bool) -> std::io::Result<()> {
+    if cells {
+        let (x, y) = renderer.size();
+        let mut buffer = Buffer::new(x, y);
+        model.draw(&mut buffer);
+
+        renderer.draw(buffer, model.cursor())
+    } else {
+        renderer.render(model.view(), model.cursor())
+    }
+}
+
+/// Send each line of standard input as a `StdinEvent`, and a `StdinClosedEvent`
+/// when there is nothing left to read.
+fn read_stdin(tx: std::sync::mpsc::Sender<model::Message>) {
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        if tx.send(Box::new(event::StdinEvent(line))).is_err() {
+            return;
+        }
+    }
+
+    let _ = tx.send(Box::new(event::StdinClosedEvent));
+}
+
+fn initialize(backend: &mut Box<dyn Backend>) -> std::io::Result<()> {
+    backend.hide_cursor()
+}
+
+fn deinitialize(backend: &mut Box<dyn Backend>) -> std::io::Result<()> {
+    backend.show_cursor()?;
+    crossterm::execute!(backend, crossterm::event::DisableMouseCapture)?;
+    backend.disable_raw_mode()
+}
+
+/// Sets the flag when dropped, including when `run` returns early with an error.
+struct CloseOnDrop(Arc<AtomicBool>);
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{Backend, TestBackend};
+    use crate::{Command, Message, Model, Program};
+
+    struct Empty;
+
+    impl Model for Empty {
+        fn update(&mut self, _: &Message) -> Option<Command> {
+            None
+        }
+
+        fn view(&self) -> String {
+            String::new()
+        }
+    }
+
+    #[test]
+    fn test_run_failed() {
+        // Events can only be taken once, so the program fails to start.
+        let mut backend = TestBackend::new(80, 24);
+        let raw = backend.raw_mode();
+        let _ = backend.events().unwrap();
+
+        assert!(Program::new(Empty).with_backend(backend).run().is_err());
+        assert!(!*raw.lock().unwrap());
+    }
+}
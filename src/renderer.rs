@@ -0,0 +1,525 @@
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use crate::cursor::Cursor;
+use crate::font::Profile;
+use crate::{ansi, backend::Backend, buffer::Buffer, foreign, utility::normalize_endings};
+
+/// Begins a synchronized update, the terminal holds the screen until the
+/// update ends so that a frame is never shown half drawn.
+///
+/// Terminals that do not support mode 2026 ignore it.
+const BEGIN_SYNC: &[u8] = b"\x1B[?2026h";
+
+/// Ends a synchronized update.
+const END_SYNC: &[u8] = b"\x1B[?2026l";
+
+/// Saves the window title on the terminal's title stack.
+const PUSH_TITLE: &[u8] = b"\x1B[22;0t";
+
+/// Restores the window title saved by `PUSH_TITLE`.
+const POP_TITLE: &[u8] = b"\x1B[23;0t";
+
+/// Draws each frame in place of the last.
+///
+/// Output written with `print` is committed above the frame, and is
+/// never cleared. Each frame is written with a single synchronized update.
+///
+/// When the output is not a terminal, frames are not drawn. Printed text
+/// and the final frame are written once, without escape sequences.
+pub struct Renderer {
+    /// Destination for all output.
+    backend: Box<dyn Backend>,
+    /// The (x, y) size of the terminal.
+    size: (u16, u16),
+    /// Display width of each line in the previous frame.
+    ///
+    /// Used to determine how many rows must be cleared before the next frame
+    /// is drawn, as lines wider than the terminal wrap onto more than one row.
+    widths: Vec<usize>,
+    /// The (line, column) of the cursor within the previous frame, if it
+    /// was placed. Otherwise it is left at the end of the last line.
+    cursor: Option<(usize, usize)>,
+    /// The last sequence written to set the shape of the cursor.
+    cursor_style: Option<String>,
+    /// True while the cursor is shown.
+    cursor_visible: bool,
+    /// The previous frame, when drawing with cells.
+    buffer: Option<Buffer>,
+    /// The previous view, when the output is not a terminal.
+    plain: Option<String>,
+    /// True once the title of the window has been saved, so that it can
+    /// be restored on exit.
+    title_saved: bool,
+    /// The colors that the terminal can display, which every frame is
+    /// downsampled to.
+    profile: Profile,
+}
+
+impl Renderer {
+    /// Return a new instance of `Renderer`.
+    ///
+    /// The size is the (x, y) size of the terminal.
+    pub fn new(backend: Box<dyn Backend>, size: (u16, u16)) -> Self {
+        Self {
+            backend,
+            size,
+            widths: Vec::new(),
+            cursor: None,
+            cursor_style: None,
+            cursor_visible: false,
+            buffer: None,
+            plain: None,
+            title_saved: false,
+            profile: Profile::TrueColor,
+        }
+    }
+
+    /// Downsample the colors of every frame and printed text to the profile.
+    ///
+    /// The default is `Profile::TrueColor`, which leaves colors as they are.
+    pub fn with_profile(self, profile: Profile) -> Self {
+        Self { profile, ..self }
+    }
+
+    /// Return a new instance of `Renderer` for output that is not a terminal.
+    pub fn plain(backend: Box<dyn Backend>) -> Self {
+        Self {
+            plain: Some(String::new()),
+            ..Self::new(backend, (0, 0))
+        }
+    }
+
+    /// Return the backend that output is written to.
+    pub fn backend(&mut self) -> &mut Box<dyn Backend> {
+        &mut self.backend
+    }
+
+    /// Return the (x, y) size of the terminal.
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Update the size of the terminal.
+    ///
+    /// Terminals reflow wrapped lines when resized, so the previous frame is
+    /// measured against the new size when it is cleared.
+    pub fn resize(&mut self, x: u16, y: u16) {
+        self.size = (x, y);
+    }
+
+    /// Replace the previous frame with the view.
+    ///
+    /// Lines that do not fit within the height of the terminal are not drawn.
+    /// The cursor is shown at its position within the view, or hidden.
+    pub fn render(&mut self, view: String, cursor: Option<Cursor>) -> std::io::Result<()> {
+        if let Some(plain) = &mut self.plain {
+            *plain = view;
+            return Ok(());
+        }
+
+        let height = usize::from(self.size.1).max(1);
+
+        let mut lines = Vec::new();
+        let mut widths = Vec::new();
+        let mut rows = 0;
+
+        for line in view.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let width = ansi::width(line);
+
+            rows += self.rows(width);
+            if rows > height && !lines.is_empty() {
+                break;
+            }
+
+            lines.push(line);
+            widths.push(width);
+        }
+
+        let mut frame = Vec::new();
+        self.queue_clear(&mut frame)?;
+        // Without a trailing newline, a frame as tall as the terminal does not scroll.
+        let lines = self.profile.downsample(&lines.join("\r\n"));
+        frame.extend_from_slice(lines.as_bytes());
+        self.widths = widths;
+
+        match cursor.filter(|c| usize::from(c.y) < self.widths.len()) {
+            Some(cursor) => {
+                let bottom = self.cursor_row();
+                self.cursor = Some((usize::from(cursor.y), usize::from(cursor.x)));
+                let row = self.cursor_row();
+
+                if bottom > row {
+                    let up = u16::try_from(bottom - row).unwrap_or(u16::MAX);
+                    crossterm::queue!(frame, crossterm::cursor::MoveUp(up))?;
+                }
+                let column = cursor.x % self.size.0.max(1);
+                crossterm::queue!(frame, crossterm::cursor::MoveToColumn(column))?;
+                self.queue_show_cursor(&mut frame, &cursor);
+            }
+            None => self.queue_hide_cursor(&mut frame),
+        }
+
+        self.write_frame(&frame)
+    }
+
+    /// Replace the previous buffer with the next, writing only the cells
+    /// that have changed.
+    ///
+    /// The buffer is drawn from the top left corner of the terminal, which
+    /// is cleared first if the previous buffer was not the same size. The
+    /// cursor is shown at its position within the buffer, or hidden.
+    pub fn draw(&mut self, next: Buffer, cursor: Option<Cursor>) -> std::io::Result<()> {
+        let prev = self.buffer.take().unwrap_or_else(|| Buffer::new(0, 0));
+
+        let mut frame = next.diff(&prev, self.profile).into_bytes();
+        match cursor.filter(|c| c.x < next.width() && c.y < next.height()) {
+            Some(cursor) => {
+                crossterm::queue!(frame, crossterm::cursor::MoveTo(cursor.x, cursor.y))?;
+                self.queue_show_cursor(&mut frame, &cursor);
+            }
+            None => self.queue_hide_cursor(&mut frame),
+        }
+
+        self.write_frame(&frame)?;
+        self.buffer = Some(next);
+
+        Ok(())
+    }
+
+    /// Write text above the current frame, where it is preserved.
+    ///
+    /// The frame is cleared, and must be drawn again with `render`.
+    pub fn print(&mut self, text: String) -> std::io::Result<()> {
+        if self.plain.is_some() {
+            return write_plain(&mut self.backend, &text);
+        }
+
+        let mut frame = Vec::new();
+        self.queue_clear(&mut frame)?;
+        let text = self.profile.downsample(&normalize_endings(text));
+        frame.extend_from_slice(text.as_bytes());
+
+        self.write_frame(&frame)
+    }
+
+    /// Move below the current frame, so that it is left in place.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        if let Some(view) = self.plain.take() {
+            self.plain = Some(String::new());
+            return write_plain(&mut self.backend, &view);
+        }
+
+        if !self.widths.is_empty() {
+            let mut out = Vec::new();
+
+            let row = self.cursor_row();
+            self.cursor = None;
+            let bottom = self.cursor_row();
+            if bottom > row {
+                let down = u16::try_from(bottom - row).unwrap_or(u16::MAX);
+                crossterm::queue!(out, crossterm::cursor::MoveDown(down))?;
+            }
+            out.extend_from_slice(b"\r\n");
+
+            self.backend.write_all(&out)?;
+            self.backend.flush()?;
+            self.widths.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Restore the default shape of the cursor, if it was changed.
+    pub fn reset_cursor(&mut self) -> std::io::Result<()> {
+        if self.cursor_style.take().is_none() {
+            return Ok(());
+        }
+
+        self.backend.write_all(b"\x1B[0 q")?;
+        self.backend.flush()
+    }
+
+    /// Set the title of the window, saving the previous title the first time.
+    pub fn set_title(&mut self, title: &str) -> std::io::Result<()> {
+        if self.plain.is_some() {
+            return Ok(());
+        }
+
+        let mut out = Vec::new();
+        if !self.title_saved {
+            out.extend_from_slice(PUSH_TITLE);
+            self.title_saved = true;
+        }
+
+        // Control characters would end the sequence early.
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        write!(out, "\x1B]0;{}\x07", title)?;
+
+        self.backend.write_all(&out)?;
+        self.backend.flush()
+    }
+
+    /// Restore the title of the window saved by `set_title`.
+    pub fn restore_title(&mut self) -> std::io::Result<()> {
+        if !self.title_saved {
+            return Ok(());
+        }
+
+        self.title_saved = false;
+        self.backend.write_all(POP_TITLE)?;
+        self.backend.flush()
+    }
+
+    /// Report the working directory to the terminal.
+    pub fn set_directory(&mut self, path: &Path) -> std::io::Result<()> {
+        if self.plain.is_some() {
+            return Ok(());
+        }
+
+        let host = unsafe { foreign::get_hostname() }.unwrap_or_default();
+        write!(
+            self.backend,
+            "\x1B]7;file://{}{}\x1B\\",
+            host,
+            encode_path(path)
+        )?;
+        self.backend.flush()
+    }
+
+    /// Return the number of rows occupied by a line of the given width.
+    fn rows(&self, width: usize) -> usize {
+        let x = usize::from(self.size.0).max(1);
+
+        width.div_ceil(x).max(1)
+    }
+
+    /// Clear the rows occupied by the previous frame.
+    ///
+    /// The cursor is left at the start of the first row.
+    pub fn clear(&mut self) -> std::io::Result<()> {
+        if let Some(plain) = &mut self.plain {
+            plain.clear();
+            return Ok(());
+        }
+
+        let mut frame = Vec::new();
+        self.queue_clear(&mut frame)?;
+
+        self.backend.write_all(&frame)?;
+        self.backend.flush()
+    }
+
+    /// Queue the sequences that clear the previous frame.
+    fn queue_clear(&mut self, out: &mut Vec<u8>) -> std::io::Result<()> {
+        if self.widths.is_empty() {
+            return Ok(());
+        }
+
+        let row = self.cursor_row();
+
+        crossterm::queue!(out, crossterm::cursor::MoveToColumn(0))?;
+        if row > 0 {
+            let up = u16::try_from(row).unwrap_or(u16::MAX);
+            crossterm::queue!(out, crossterm::cursor::MoveUp(up))?;
+        }
+        crossterm::queue!(
+            out,
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::FromCursorDown)
+        )?;
+        self.widths.clear();
+        self.cursor = None;
+
+        Ok(())
+    }
+
+    /// Return the row of the cursor, counted from the first row of the
+    /// previous frame.
+    fn cursor_row(&self) -> usize {
+        match self.cursor {
+            Some((line, column)) => {
+                let above: usize = self.widths[..line].iter().map(|w| self.rows(*w)).sum();
+                above + column / usize::from(self.size.0).max(1)
+            }
+            None => {
+                let rows: usize = self.widths.iter().map(|w| self.rows(*w)).sum();
+                rows.saturating_sub(1)
+            }
+        }
+    }
+
+    /// Queue the sequences that set the shape of the cursor and show it.
+    fn queue_show_cursor(&mut self, out: &mut Vec<u8>, cursor: &Cursor) {
+        let style = cursor.style();
+        if self.cursor_style.as_ref() != Some(&style) {
+            out.extend_from_slice(style.as_bytes());
+            self.cursor_style = Some(style);
+        }
+
+        if !self.cursor_visible {
+            out.extend_from_slice(b"\x1B[?25h");
+            self.cursor_visible = true;
+        }
+    }
+
+    /// Queue the sequence that hides the cursor, if it is shown.
+    fn queue_hide_cursor(&mut self, out: &mut Vec<u8>) {
+        if self.cursor_visible {
+            out.extend_from_slice(b"\x1B[?25l");
+            self.cursor_visible = false;
+        }
+    }
+
+    /// Write a frame with a single synchronized update.
+    fn write_frame(&mut self, frame: &[u8]) -> std::io::Result<()> {
+        let mut update = Vec::with_capacity(BEGIN_SYNC.len() + frame.len() + END_SYNC.len());
+        update.extend_from_slice(BEGIN_SYNC);
+        update.extend_from_slice(frame);
+        update.extend_from_slice(END_SYNC);
+
+        self.backend.write_all(&update)?;
+        self.backend.flush()
+    }
+}
+
+/// Return the path percent-encoded for use in a `file` URL.
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for b in path.as_os_str().as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(*b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    encoded
+}
+
+/// Write the text without escape sequences, ending with a newline.
+///
+/// Nothing is written if the text is empty.
+fn write_plain(out: &mut impl Write, text: &str) -> std::io::Result<()> {
+    let mut text = ansi::strip_ansi(text).replace("\r\n", "\n");
+    if text.is_empty() {
+        return Ok(());
+    }
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    out.write_all(text.as_bytes())?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::backend::TestBackend;
+    use crate::cursor::{Cursor, Shape};
+    use crate::renderer::Renderer;
+
+    /// Take everything written to the backend so far.
+    fn take(output: &Arc<Mutex<Vec<u8>>>) -> String {
+        String::from_utf8(std::mem::take(&mut *output.lock().unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_render_wrapped() {
+        let backend = TestBackend::new(10, 24);
+        let output = backend.output();
+        let mut renderer = Renderer::new(Box::new(backend), (10, 24));
+
+        // The first line wraps onto a second row, and escapes take no space.
+        renderer
+            .render("\x1B[1mfifteen columns\x1B[0m\nshort".to_string(), None)
+            .unwrap();
+        assert_eq!(
+            take(&output),
+            "\x1B[?2026h\x1B[1mfifteen columns\x1B[0m\r\nshort\x1B[?2026l"
+        );
+
+        // Three rows are cleared.
+        renderer.render("next".to_string(), None).unwrap();
+        assert_eq!(
+            take(&output),
+            "\x1B[?2026h\x1B[1G\x1B[2A\x1B[Jnext\x1B[?2026l"
+        );
+    }
+
+    #[test]
+    fn test_render_clipped() {
+        let backend = TestBackend::new(10, 3);
+        let output = backend.output();
+        let mut renderer = Renderer::new(Box::new(backend), (10, 3));
+
+        renderer.render("1\n2\n3\n4\n5".to_string(), None).unwrap();
+        assert_eq!(take(&output), "\x1B[?2026h1\r\n2\r\n3\x1B[?2026l");
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let backend = TestBackend::new(10, 3);
+        let output = backend.output();
+        let mut renderer = Renderer::plain(Box::new(backend));
+
+        // Frames are not drawn, printed text is written without escapes.
+        renderer
+            .render("\x1B[1mfirst\x1B[0m".to_string(), None)
+            .unwrap();
+        renderer.print("\x1B[32mdone\x1B[0m".to_string()).unwrap();
+        assert_eq!(take(&output), "done\n");
+
+        // Only the final frame is written.
+        renderer
+            .render("\x1B[1mlast\x1B[0m".to_string(), None)
+            .unwrap();
+        renderer.finish().unwrap();
+        assert_eq!(take(&output), "last\n");
+    }
+
+    #[test]
+    fn test_title() {
+        let backend = TestBackend::new(10, 3);
+        let output = backend.output();
+        let mut renderer = Renderer::new(Box::new(backend), (10, 3));
+
+        // The previous title is saved once, and restored on exit.
+        renderer.set_title("one").unwrap();
+        renderer.set_title("two\x07").unwrap();
+        renderer.restore_title().unwrap();
+        assert_eq!(
+            take(&output),
+            "\x1B[22;0t\x1B]0;one\x07\x1B]0;two\x07\x1B[23;0t"
+        );
+    }
+
+    #[test]
+    fn test_render_cursor() {
+        let backend = TestBackend::new(10, 24);
+        let output = backend.output();
+        let mut renderer = Renderer::new(Box::new(backend), (10, 24));
+
+        // The cursor is placed on the wrapped row of the first line.
+        let cursor = Cursor::new(12, 0).with_shape(Shape::Bar);
+        renderer
+            .render("fifteen columns\nshort".to_string(), Some(cursor))
+            .unwrap();
+        assert_eq!(
+            take(&output),
+            "\x1B[?2026hfifteen columns\r\nshort\x1B[1A\x1B[3G\x1B[5 q\x1B[?25h\x1B[?2026l"
+        );
+
+        // Clearing starts from the row of the cursor, which is then hidden.
+        renderer.render("next".to_string(), None).unwrap();
+        assert_eq!(
+            take(&output),
+            "\x1B[?2026h\x1B[1G\x1B[1A\x1B[Jnext\x1B[?25l\x1B[?2026l"
+        );
+    }
+}
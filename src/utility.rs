@@ -13,5 +13,5 @@ pub fn normalize_endings(initial: String) -> String {
         initial
     };
 
-    initial.replace('\n', "\r\n")
+    initial.replace("\r\n", "\n").replace('\n', "\r\n")
 }
@@ -180,6 +180,7 @@ impl Pager {
     /// let total_pages = pager.get_total();
     /// assert_eq!(total_pages, 20);
     /// ```
+    #[allow(clippy::manual_is_multiple_of)]
     pub fn set_total_by_len(&mut self, len: usize) -> usize {
         if len < 1 {
             return self.total;
@@ -345,7 +346,7 @@ impl Pager {
     /// assert_eq!(pager.is_on_last_page(), true);
     /// ```
     pub fn is_on_last_page(&self) -> bool {
-        self.current == self.total.checked_sub(1).unwrap_or(0)
+        self.current == self.total.saturating_sub(1)
     }
 
     /// Return true if the pager is on the first page.
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use crate::model::closable_stream;
+use crate::{foreign, Command};
+
+/// Milliseconds to wait for events before checking if the application has exited.
+const POLL_TIMEOUT: libc::c_int = 100;
+
+/// Events that are watched for on each path.
+const MASK: u32 = libc::IN_CREATE
+    | libc::IN_MOVED_TO
+    | libc::IN_MODIFY
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF;
+
+/// Describes a change made to a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// The path was created, or moved into a watched directory.
+    Created,
+    /// The contents of the path were written to.
+    Modified,
+    /// The path was removed, or moved away.
+    Removed,
+}
+
+/// A message describing a change to a watched path.
+///
+/// When a directory is watched, `path` is the entry within the directory
+/// that changed, otherwise it is the watched path itself.
+pub struct WatchMessage {
+    /// The path that changed.
+    pub path: PathBuf,
+    /// The kind of change.
+    pub change: Change,
+}
+
+/// Return a command that will deliver a `WatchMessage` each time one of
+/// the paths changes.
+///
+/// Paths may be files or directories. Directories are not watched recursively.
+///
+/// Many editors save a file by replacing it, which ends a watch placed on the
+/// file itself. Watch the parent directory instead to keep receiving changes.
+/// The command ends once every watch has ended, or the application has exited.
+///
+/// # Errors
+///
+/// Returns an error if any of the paths cannot be watched, for example
+/// because it does not exist.
+///
+/// # Examples
+///
+/// ```
+/// let directory = std::env::temp_dir();
+///
+/// // Return `cmd` from `init` or `update` to begin receiving messages.
+/// let cmd = locket::watch::watch([&directory]).unwrap();
+/// ```
+pub fn watch<I, P>(paths: I) -> std::io::Result<Command>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let fd = unsafe { foreign::inotify_init() }?;
+
+    let mut watches = HashMap::new();
+    for path in paths {
+        let path = path.as_ref();
+        let wd = unsafe { foreign::inotify_add_watch(&fd, path, MASK) }?;
+        watches.insert(wd, path.to_path_buf());
+    }
+
+    let mut file = std::fs::File::from(fd);
+
+    Ok(closable_stream(move |tx, closed| {
+        // Large enough to hold at least one event with the longest possible name.
+        let mut buffer = [0; 4096];
+
+        // Wait with a timeout, so the thread ends soon after the application.
+        while !closed.load(Ordering::SeqCst) {
+            match unsafe { foreign::poll_read(&[file.as_raw_fd()], POLL_TIMEOUT) } {
+                Ok(ready) if ready[0] => {}
+                Ok(_) => continue,
+                Err(_) => return,
+            }
+
+            let len = match file.read(&mut buffer) {
+                Ok(0) => return,
+                Ok(len) => len,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => return,
+            };
+
+            for message in parse(&buffer[..len], &mut watches) {
+                if tx.send(Box::new(message)).is_err() {
+                    return;
+                }
+            }
+
+            // Every watch has been removed, so no more events will arrive.
+            if watches.is_empty() {
+                return;
+            }
+        }
+    }))
+}
+
+/// Parse the events in a buffer read from an inotify descriptor.
+///
+/// Events not described by `Change` are skipped. A watch is removed from
+/// `watches` once the kernel reports that it has ended.
+fn parse(buffer: &[u8], watches: &mut HashMap<libc::c_int, PathBuf>) -> Vec<WatchMessage> {
+    let header = std::mem::size_of::<libc::inotify_event>();
+
+    let mut messages = Vec::new();
+    let mut offset = 0;
+
+    while offset + header <= buffer.len() {
+        let event = unsafe {
+            std::ptr::read_unaligned(buffer[offset..].as_ptr() as *const libc::inotify_event)
+        };
+
+        let start = offset + header;
+        offset = start + event.len as usize;
+
+        // Sent after the last event of a watch, such as when the path is removed.
+        if event.mask & libc::IN_IGNORED != 0 {
+            watches.remove(&event.wd);
+            continue;
+        }
+
+        let change = if event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+            Change::Created
+        } else if event.mask & libc::IN_MODIFY != 0 {
+            Change::Modified
+        } else if event.mask
+            & (libc::IN_DELETE | libc::IN_MOVED_FROM | libc::IN_DELETE_SELF | libc::IN_MOVE_SELF)
+            != 0
+        {
+            Change::Removed
+        } else {
+            continue;
+        };
+
+        let Some(root) = watches.get(&event.wd) else {
+            continue;
+        };
+
+        // The name is padded with nul bytes, and empty for events on the path itself.
+        let name = buffer
+            .get(start..offset)
+            .and_then(|name| name.split(|b| *b == 0).next())
+            .unwrap_or_default();
+
+        let path = if name.is_empty() {
+            root.clone()
+        } else {
+            root.join(std::ffi::OsStr::from_bytes(name))
+        };
+
+        messages.push(WatchMessage { path, change });
+    }
+
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::model::ClosableStreamMessage;
+    use crate::watch::{watch, Change, WatchMessage};
+
+    fn start(
+        directory: &std::path::Path,
+    ) -> (
+        std::sync::mpsc::Receiver<crate::Message>,
+        Arc<AtomicBool>,
+        std::thread::JoinHandle<()>,
+    ) {
+        let cmd = watch([directory]).unwrap();
+        let stream = cmd().unwrap().downcast::<ClosableStreamMessage>().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let flag = closed.clone();
+        let thread = std::thread::spawn(move || (stream.0)(tx, flag));
+
+        (rx, closed, thread)
+    }
+
+    fn next(rx: &std::sync::mpsc::Receiver<crate::Message>) -> Box<WatchMessage> {
+        rx.recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .downcast::<WatchMessage>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_watch() {
+        let directory = std::env::temp_dir().join(format!("locket-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let (rx, _closed, thread) = start(&directory);
+
+        let file = directory.join("config.toml");
+        std::fs::write(&file, "key = 1").unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let mut changes = Vec::new();
+        while changes.last() != Some(&Change::Removed) {
+            let message = next(&rx);
+            assert_eq!(message.path, file);
+            changes.push(message.change);
+        }
+
+        assert_eq!(
+            changes,
+            vec![Change::Created, Change::Modified, Change::Removed]
+        );
+
+        // Removing the watched directory ends the watch, and the stream.
+        std::fs::remove_dir(&directory).unwrap();
+
+        let message = next(&rx);
+        assert_eq!((message.path, message.change), (directory, Change::Removed));
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_watch_closed() {
+        let directory =
+            std::env::temp_dir().join(format!("locket-watch-closed-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // The stream ends once the application has exited, without any events.
+        let (_rx, closed, thread) = start(&directory);
+        closed.store(true, Ordering::SeqCst);
+        thread.join().unwrap();
+
+        std::fs::remove_dir(&directory).unwrap();
+    }
+}